@@ -0,0 +1,246 @@
+use crate::api::policy::backoff;
+use crate::api::*;
+use crate::logging;
+use crate::models::TaskId;
+use reqwest::multipart::{Form, Part};
+use reqwest::{Method, RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+// Header the worker uses to recognise a retried write it has already applied
+const IDEMPOTENCY_KEY: &str = "Idempotency-Key";
+
+/// Typed client for the Jeff AI worker.
+///
+/// Cloning is cheap: the underlying `reqwest::Client` is reference counted,
+/// so every clone shares one connection pool.
+#[derive(Clone)]
+pub struct JeffApiClient {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+    cancellation: Option<CancellationToken>,
+}
+
+impl JeffApiClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_http_client(reqwest::Client::new(), base_url)
+    }
+
+    /// Builds a client on top of an existing pool.
+    pub fn with_http_client(http: reqwest::Client, base_url: impl Into<String>) -> Self {
+        Self {
+            http,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            token: None,
+            cancellation: None,
+        }
+    }
+
+    /// Returns a copy that sends `token` as a bearer token on every request.
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Returns a copy whose requests fail with `ApiError::Cancelled` as soon
+    /// as `cancellation` is cancelled.
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = Some(cancellation);
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let builder = self.http.request(method, format!("{}{}", self.base_url, path));
+        match self.token.as_deref() {
+            Some(token) => builder.bearer_auth(token),
+            None => builder,
+        }
+    }
+
+    /// Tags a write with a fresh idempotency key. Every retry of the request
+    /// reuses it, so the backend applies the write at most once.
+    fn idempotent(builder: RequestBuilder) -> RequestBuilder {
+        Self::idempotent_with(builder, &Uuid::new_v4().to_string())
+    }
+
+    /// Like `idempotent`, with a key the caller keeps so the write can be
+    /// retried safely after this call has returned.
+    fn idempotent_with(builder: RequestBuilder, key: &str) -> RequestBuilder {
+        builder.header(IDEMPOTENCY_KEY, key)
+    }
+
+    /// Sends the request under `policy`: each attempt gets the policy's
+    /// timeout, and transient failures are retried with jittered exponential
+    /// backoff while attempts remain and the body can be replayed.
+    async fn send(&self, policy: RequestPolicy, builder: RequestBuilder) -> Result<reqwest::Response, ApiError> {
+        let mut builder = builder.timeout(policy.timeout);
+        let mut attempt = 1;
+
+        loop {
+            // Keep a copy for the next attempt before this one consumes the
+            // builder. Streamed bodies can't be copied and get no retries.
+            let next = (attempt < policy.max_attempts).then(|| builder.try_clone()).flatten();
+
+            let error = match self.send_once(builder).await {
+                Ok(response) => return Ok(response),
+                Err(e) => e,
+            };
+
+            let Some(next) = next.filter(|_| error.is_transient()) else {
+                return Err(error);
+            };
+
+            let delay = backoff(attempt);
+            log::warn!("Request failed ({}), retrying in {:?}", error, delay);
+            self.sleep(delay).await?;
+
+            attempt += 1;
+            builder = next;
+        }
+    }
+
+    async fn send_once(&self, builder: RequestBuilder) -> Result<reqwest::Response, ApiError> {
+        let response = match self.cancellation.as_ref() {
+            Some(cancellation) => tokio::select! {
+                response = builder.send() => response,
+                _ = cancellation.cancelled() => return Err(ApiError::Cancelled),
+            },
+            None => builder.send().await,
+        }
+        .map_err(ApiError::from_reqwest)?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let body = response.text().await.unwrap_or_default();
+        log::error!("Backend returned {}: {}", status, logging::content(&body));
+        Err(ApiError::from_response(status.as_u16(), &body))
+    }
+
+    async fn sleep(&self, delay: std::time::Duration) -> Result<(), ApiError> {
+        match self.cancellation.as_ref() {
+            Some(cancellation) => tokio::select! {
+                _ = tokio::time::sleep(delay) => Ok(()),
+                _ = cancellation.cancelled() => Err(ApiError::Cancelled),
+            },
+            None => {
+                tokio::time::sleep(delay).await;
+                Ok(())
+            }
+        }
+    }
+
+    /// Sends the request and decodes the response into `T`. A body that
+    /// doesn't match `T` or fails its validation is an `ApiError::Decode`, so
+    /// backend shape changes surface as errors instead of broken UI.
+    async fn send_json<T: DeserializeOwned + Validate>(
+        &self,
+        policy: RequestPolicy,
+        builder: RequestBuilder,
+    ) -> Result<T, ApiError> {
+        let response = self.send(policy, builder).await?;
+        let path = response.url().path().to_string();
+        let body = response.text().await.map_err(ApiError::from_reqwest)?;
+
+        let value: T = serde_json::from_str(&body).map_err(|e| {
+            log::error!("Unexpected response from {}: {}", path, e);
+            ApiError::Decode(e.to_string())
+        })?;
+        value.validate().map_err(|e| {
+            log::error!("Invalid response from {}: {}", path, e);
+            ApiError::Decode(e)
+        })?;
+        Ok(value)
+    }
+
+    pub async fn capture_user(&self, request: &CaptureUserRequest) -> Result<CaptureUserResponse, ApiError> {
+        let builder = Self::idempotent(self.request(Method::POST, "/api/capture").json(request));
+        self.send_json(RequestPolicy::SAFE_WRITE, builder).await
+    }
+
+    pub async fn transcribe(&self, request: TranscribeRequest) -> Result<TranscribeResponse, ApiError> {
+        let part = Part::bytes(request.audio)
+            .file_name("audio.wav")
+            .mime_str("audio/wav")
+            .map_err(ApiError::from_reqwest)?;
+
+        let form = Form::new()
+            .part("file", part)
+            .text("refine", request.refine.to_string())
+            .text("userId", request.user_id);
+
+        let builder = self.request(Method::POST, "/api/transcribe").multipart(form);
+        self.send_json(RequestPolicy::TRANSCRIBE, builder).await
+    }
+
+    pub async fn list_tasks(&self) -> Result<Vec<TaskRecord>, ApiError> {
+        self.send_json(RequestPolicy::READ, self.request(Method::GET, "/api/tasks")).await
+    }
+
+    /// Creates a task. The backend applies at most one create per
+    /// `idempotency_key`.
+    pub async fn create_task(&self, request: &CreateTaskRequest, idempotency_key: &str) -> Result<TaskRecord, ApiError> {
+        let builder = Self::idempotent_with(self.request(Method::POST, "/api/tasks").json(request), idempotency_key);
+        self.send_json(RequestPolicy::SAFE_WRITE, builder).await
+    }
+
+    pub async fn update_task(&self, task_id: &TaskId, request: &UpdateTaskRequest) -> Result<TaskRecord, ApiError> {
+        let path = format!("/api/tasks/{}", task_id);
+        self.send_json(RequestPolicy::SAFE_WRITE, self.request(Method::PATCH, &path).json(request)).await
+    }
+
+    pub async fn delete_task(&self, task_id: &TaskId) -> Result<(), ApiError> {
+        let path = format!("/api/tasks/{}", task_id);
+        self.send(RequestPolicy::SAFE_WRITE, self.request(Method::DELETE, &path)).await?;
+        Ok(())
+    }
+
+    pub async fn list_refinements(&self) -> Result<Vec<Refinement>, ApiError> {
+        self.send_json(RequestPolicy::READ, self.request(Method::GET, "/api/refinements")).await
+    }
+
+    pub async fn refine_text(&self, request: &RefineTextRequest) -> Result<RefineTextResponse, ApiError> {
+        let builder = Self::idempotent(self.request(Method::POST, "/api/refinements").json(request));
+        self.send_json(RequestPolicy::REFINE, builder).await
+    }
+
+    pub async fn convert_to_markdown(&self, request: &ConvertToMarkdownRequest) -> Result<MarkdownResponse, ApiError> {
+        let builder = self.request(Method::POST, "/api/refinements/convert-to-markdown");
+        self.send_json(RequestPolicy::REFINE, Self::idempotent(builder.json(request))).await
+    }
+
+    /// Returns `None` for users that haven't saved any settings yet.
+    pub async fn get_settings(&self) -> Result<Option<UserSettings>, ApiError> {
+        match self.send_json(RequestPolicy::READ, self.request(Method::GET, "/api/settings")).await {
+            Err(ApiError::Server { status, .. }) if status == StatusCode::NOT_FOUND.as_u16() => Ok(None),
+            result => result,
+        }
+    }
+
+    pub async fn create_settings(&self, request: &UserSettingsRequest) -> Result<UserSettings, ApiError> {
+        let builder = Self::idempotent(self.request(Method::POST, "/api/settings").json(request));
+        self.send_json(RequestPolicy::SAFE_WRITE, builder).await
+    }
+
+    pub async fn update_settings(&self, request: &UserSettingsRequest) -> Result<UserSettings, ApiError> {
+        self.send_json(RequestPolicy::SAFE_WRITE, self.request(Method::PATCH, "/api/settings").json(request)).await
+    }
+
+    pub async fn geocode(&self, query: &str) -> Result<Vec<GeocodeResult>, ApiError> {
+        let builder = self.request(Method::GET, "/api/weather/geocode").query(&[("q", query)]);
+        self.send_json(RequestPolicy::WEATHER, builder).await
+    }
+
+    pub async fn weather_forecast(&self, lat: f64, lon: f64) -> Result<WeatherResponse, ApiError> {
+        let builder = self.request(Method::GET, "/api/weather").query(&[("lat", lat), ("lon", lon)]);
+        self.send_json(RequestPolicy::WEATHER, builder).await
+    }
+}
@@ -0,0 +1,62 @@
+use serde_json::Value;
+use std::fmt;
+
+/// Everything that can go wrong talking to the backend, mapped the same way
+/// for every endpoint.
+#[derive(Debug)]
+pub enum ApiError {
+    /// The request never got a response: DNS, TLS, connection refused...
+    Network(String),
+    Timeout,
+    /// The backend answered with a non-2xx status. `message` is the `error`
+    /// field of the JSON body when there is one, otherwise the raw body.
+    Server { status: u16, message: String },
+    /// The response body didn't match the expected shape.
+    Decode(String),
+    /// Aborted by logout or a profile switch.
+    Cancelled,
+}
+
+impl ApiError {
+    pub(crate) fn from_reqwest(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            Self::Timeout
+        } else if error.is_decode() {
+            Self::Decode(error.to_string())
+        } else {
+            Self::Network(error.to_string())
+        }
+    }
+
+    /// Whether sending the same request again might succeed.
+    pub(crate) fn is_transient(&self) -> bool {
+        match self {
+            Self::Network(_) | Self::Timeout => true,
+            Self::Server { status, .. } => *status >= 500 || *status == 429,
+            Self::Decode(_) | Self::Cancelled => false,
+        }
+    }
+
+    pub(crate) fn from_response(status: u16, body: &str) -> Self {
+        let message = serde_json::from_str::<Value>(body)
+            .ok()
+            .and_then(|json| json.get("error").and_then(Value::as_str).map(str::to_string))
+            .unwrap_or_else(|| body.to_string());
+
+        Self::Server { status, message }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Network(e) => write!(f, "Network error: {}", e),
+            Self::Timeout => write!(f, "Request timed out"),
+            Self::Server { status, message } => write!(f, "Server error ({}): {}", status, message),
+            Self::Decode(e) => write!(f, "Unexpected response from server: {}", e),
+            Self::Cancelled => write!(f, "Request cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
@@ -0,0 +1,11 @@
+mod client;
+mod error;
+mod policy;
+mod types;
+mod weather;
+
+pub use client::JeffApiClient;
+pub use error::ApiError;
+pub use policy::RequestPolicy;
+pub use types::*;
+pub use weather::*;
@@ -0,0 +1,64 @@
+use rand::Rng;
+use std::time::Duration;
+
+// First retry waits up to this long, doubling with every further attempt
+const BACKOFF_BASE: Duration = Duration::from_millis(250);
+
+// Upper bound for a single backoff, however many attempts came before
+const BACKOFF_CAP: Duration = Duration::from_secs(4);
+
+/// How long one attempt at a request may take, and how many attempts it gets.
+///
+/// Only requests that are safe to repeat get more than one attempt: reads,
+/// and writes that carry an `Idempotency-Key` so the backend can drop
+/// duplicates.
+#[derive(Debug, Clone, Copy)]
+pub struct RequestPolicy {
+    pub timeout: Duration,
+    pub max_attempts: u32,
+}
+
+impl RequestPolicy {
+    /// Cheap reads: tasks, settings, refinements.
+    pub const READ: Self = Self {
+        timeout: Duration::from_secs(10),
+        max_attempts: 3,
+    };
+
+    /// Weather goes through a third-party API behind the worker.
+    pub const WEATHER: Self = Self {
+        timeout: Duration::from_secs(15),
+        max_attempts: 3,
+    };
+
+    /// Writes that are safe to repeat: PATCH and DELETE, and POSTs sent
+    /// with an idempotency key.
+    pub const SAFE_WRITE: Self = Self {
+        timeout: Duration::from_secs(15),
+        max_attempts: 3,
+    };
+
+    /// Calls that wait on a language model. Always sent with an idempotency
+    /// key.
+    pub const REFINE: Self = Self {
+        timeout: Duration::from_secs(60),
+        max_attempts: 2,
+    };
+
+    /// Audio upload plus speech-to-text, by far the slowest call. The body
+    /// is streamed from a multipart form and can't be replayed.
+    pub const TRANSCRIBE: Self = Self {
+        timeout: Duration::from_secs(120),
+        max_attempts: 1,
+    };
+}
+
+/// Delay before retry number `attempt` (starting at 1), with full jitter so
+/// clients that failed together don't all come back at the same moment.
+pub(crate) fn backoff(attempt: u32) -> Duration {
+    let ceiling = BACKOFF_BASE
+        .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+        .min(BACKOFF_CAP);
+    let millis = rand::thread_rng().gen_range(0..=ceiling.as_millis() as u64);
+    Duration::from_millis(millis)
+}
@@ -0,0 +1,264 @@
+use crate::models::{ExistingUser, NewTask, Task, TaskChanges, TaskId};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+// Request and response bodies for each endpoint, field names match the
+// worker's JSON. Types the frontend receives derive `TS`, their TypeScript
+// bindings are written to src/types/bindings by `cargo test`.
+
+/// Checks a decoded response for values the UI can't work with. Decoding
+/// already enforces the shape, this catches what the types can't express.
+pub trait Validate {
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+impl<T: Validate> Validate for Vec<T> {
+    fn validate(&self) -> Result<(), String> {
+        self.iter().try_for_each(Validate::validate)
+    }
+}
+
+impl<T: Validate> Validate for Option<T> {
+    fn validate(&self) -> Result<(), String> {
+        self.as_ref().map_or(Ok(()), Validate::validate)
+    }
+}
+
+pub(crate) fn check_coordinates(lat: f64, lon: f64) -> Result<(), String> {
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return Err(format!("Coordinates out of range: {}, {}", lat, lon));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureUserRequest {
+    pub auth_user_id: String,
+    pub email: String,
+    pub display_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CaptureUserResponse {
+    pub user: ExistingUser,
+}
+
+impl Validate for CaptureUserResponse {
+    fn validate(&self) -> Result<(), String> {
+        if self.user.id.is_empty() {
+            return Err("User has no ID".to_string());
+        }
+        Ok(())
+    }
+}
+
+pub struct TranscribeRequest {
+    pub audio: Vec<u8>,
+    pub refine: bool,
+    pub user_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TranscribeResponse {
+    pub transcription: Option<String>,
+    pub refined: Option<String>,
+    pub message: Option<String>,
+}
+
+impl TranscribeResponse {
+    /// The backend answers with a message instead of text when the audio had
+    /// no speech in it.
+    pub fn no_dialog(&self) -> bool {
+        self.message.as_deref() == Some("No dialog detected")
+    }
+}
+
+impl Validate for TranscribeResponse {
+    fn validate(&self) -> Result<(), String> {
+        if self.transcription.is_none() && self.refined.is_none() && self.message.is_none() {
+            return Err("Transcription response is empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// The worker's JSON for a task. It names the fields `task` and `done`, and
+/// the PATCH body calls the assigned date `day`, so these only exist at the
+/// edge and are mapped to and from `models::Task` below.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskRecord {
+    pub id: TaskId,
+    pub task: String,
+    pub done: bool,
+    pub assigned_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+impl Validate for TaskRecord {
+    fn validate(&self) -> Result<(), String> {
+        if self.id.as_str().is_empty() {
+            return Err("Task has no ID".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl From<TaskRecord> for Task {
+    fn from(record: TaskRecord) -> Self {
+        Self {
+            id: record.id,
+            content: record.task,
+            completed: record.done,
+            assigned_date: record.assigned_date,
+            priority: None,
+            tags: Vec::new(),
+            recurrence: None,
+            due_time: None,
+            created_at: record.created_at,
+            updated_at: record.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTaskRequest {
+    pub task: String,
+    pub done: bool,
+    pub user_id: String,
+    pub assigned_date: String,
+}
+
+impl CreateTaskRequest {
+    pub fn new(task: NewTask, user_id: String) -> Self {
+        Self {
+            task: task.content,
+            done: false,
+            user_id,
+            assigned_date: task.assigned_date,
+        }
+    }
+}
+
+/// Only the fields that are set get sent, so a PATCH never clears the others.
+#[derive(Debug, Serialize, Default)]
+pub struct UpdateTaskRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub done: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub day: Option<String>,
+}
+
+impl From<TaskChanges> for UpdateTaskRequest {
+    fn from(changes: TaskChanges) -> Self {
+        Self {
+            task: changes.content,
+            done: changes.completed,
+            day: changes.assigned_date,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct Refinement {
+    pub id: String,
+    pub user_id: String,
+    pub original_text: String,
+    pub original_text_word_count: u32,
+    pub refined_text: String,
+    pub refined_text_word_count: u32,
+    pub explanation: Option<String>,
+    pub vector: Vec<f32>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Validate for Refinement {}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefineTextRequest {
+    pub original_text: String,
+    pub additional_context: Option<String>,
+    pub user_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct RefineTextResponse {
+    pub refined_text: String,
+    pub explanation: Option<String>,
+}
+
+impl Validate for RefineTextResponse {}
+
+#[derive(Debug, Serialize)]
+pub struct ConvertToMarkdownRequest {
+    pub html: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MarkdownResponse {
+    pub markdown: String,
+}
+
+impl Validate for MarkdownResponse {}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum Units {
+    Imperial,
+    Metric,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserSettingsRequest {
+    pub user_id: String,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub country: Option<String>,
+    pub units: Units,
+    pub language: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct UserSettings {
+    pub id: String,
+    pub user_id: String,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub country: Option<String>,
+    pub units: Units,
+    pub language: String,
+}
+
+impl Validate for UserSettings {
+    fn validate(&self) -> Result<(), String> {
+        match (self.lat, self.lon) {
+            (Some(lat), Some(lon)) => check_coordinates(lat, lon),
+            (None, None) => Ok(()),
+            _ => Err("Settings have only one of latitude and longitude".to_string()),
+        }
+    }
+}
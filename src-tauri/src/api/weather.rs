@@ -0,0 +1,170 @@
+use crate::api::types::{check_coordinates, Validate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use ts_rs::TS;
+
+// The worker passes OpenWeather's geocoding and One Call responses through
+// unchanged, so these keep OpenWeather's snake_case field names
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export)]
+pub struct GeocodeResult {
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub country: String,
+    pub state: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub local_names: Option<HashMap<String, String>>,
+}
+
+impl Validate for GeocodeResult {
+    fn validate(&self) -> Result<(), String> {
+        check_coordinates(self.lat, self.lon)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export)]
+pub struct WeatherCondition {
+    pub id: u32,
+    pub main: String,
+    pub description: String,
+    pub icon: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export)]
+pub struct CurrentWeather {
+    #[ts(type = "number")]
+    pub dt: i64,
+    #[ts(type = "number")]
+    pub sunrise: i64,
+    #[ts(type = "number")]
+    pub sunset: i64,
+    pub temp: f64,
+    pub feels_like: f64,
+    pub pressure: f64,
+    pub humidity: f64,
+    pub dew_point: f64,
+    pub uvi: f64,
+    pub clouds: f64,
+    #[serde(default)]
+    pub visibility: f64,
+    pub wind_speed: f64,
+    pub wind_deg: f64,
+    pub weather: Vec<WeatherCondition>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export)]
+pub struct HourlyRain {
+    #[serde(rename = "1h")]
+    pub one_hour: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export)]
+pub struct HourlyWeather {
+    #[ts(type = "number")]
+    pub dt: i64,
+    pub temp: f64,
+    pub feels_like: f64,
+    pub pressure: f64,
+    pub humidity: f64,
+    pub dew_point: f64,
+    pub uvi: f64,
+    pub clouds: f64,
+    #[serde(default)]
+    pub visibility: f64,
+    pub wind_speed: f64,
+    pub wind_deg: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub wind_gust: Option<f64>,
+    pub weather: Vec<WeatherCondition>,
+    pub pop: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub rain: Option<HourlyRain>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export)]
+pub struct DailyTemp {
+    pub day: f64,
+    pub min: f64,
+    pub max: f64,
+    pub night: f64,
+    pub eve: f64,
+    pub morn: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export)]
+pub struct DailyFeelsLike {
+    pub day: f64,
+    pub night: f64,
+    pub eve: f64,
+    pub morn: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export)]
+pub struct DailyWeather {
+    #[ts(type = "number")]
+    pub dt: i64,
+    #[ts(type = "number")]
+    pub sunrise: i64,
+    #[ts(type = "number")]
+    pub sunset: i64,
+    #[ts(type = "number")]
+    pub moonrise: i64,
+    #[ts(type = "number")]
+    pub moonset: i64,
+    pub moon_phase: f64,
+    #[serde(default)]
+    pub summary: String,
+    pub temp: DailyTemp,
+    pub feels_like: DailyFeelsLike,
+    pub pressure: f64,
+    pub humidity: f64,
+    pub dew_point: f64,
+    pub wind_speed: f64,
+    pub wind_deg: f64,
+    #[serde(default)]
+    pub wind_gust: f64,
+    pub weather: Vec<WeatherCondition>,
+    pub clouds: f64,
+    pub pop: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub rain: Option<f64>,
+    pub uvi: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export)]
+pub struct WeatherForecast {
+    pub lat: f64,
+    pub lon: f64,
+    pub timezone: String,
+    #[ts(type = "number")]
+    pub timezone_offset: i64,
+    pub current: CurrentWeather,
+    pub hourly: Vec<HourlyWeather>,
+    pub daily: Vec<DailyWeather>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export)]
+pub struct WeatherResponse {
+    pub data: WeatherForecast,
+}
+
+impl Validate for WeatherResponse {
+    fn validate(&self) -> Result<(), String> {
+        check_coordinates(self.data.lat, self.data.lon)
+    }
+}
@@ -0,0 +1,275 @@
+use crate::audio::{run_writer, PreRollAttachment, SampleWriter};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use rtrb::RingBuffer;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::time::Duration;
+
+// How often the capture thread checks whether the default input device changed
+const DEVICE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// Seconds of audio the ring buffer can hold before the callback starts
+// dropping buffers, gives the writer thread plenty of slack on slow disks
+const RING_BUFFER_SECONDS: usize = 2;
+
+/// Messages sent to the capture thread, either by `stop_recording` or by the
+/// cpal error callback.
+#[derive(Debug)]
+pub enum RecorderMessage {
+    Stop,
+    DeviceLost(String),
+}
+
+/// How a capture session ended. In both cases the WAV file has been finalized
+/// and contains everything captured up to that point.
+#[derive(Debug)]
+pub enum CaptureOutcome {
+    Stopped,
+    DeviceLost(String),
+}
+
+/// Events raised while capturing that the caller may want to surface.
+#[derive(Debug)]
+pub enum CaptureEvent {
+    DeviceChanged(String),
+}
+
+pub fn default_input_device_name(host: &cpal::Host) -> Option<String> {
+    host.default_input_device().and_then(|device| device.name().ok())
+}
+
+/// Finds a stream config on `device` that produces the same sample layout as
+/// the WAV file we're already writing to, so a migrated stream can keep
+/// appending to it.
+fn matching_input_config(device: &cpal::Device, spec: &hound::WavSpec) -> Option<cpal::StreamConfig> {
+    let configs = device.supported_input_configs().ok()?;
+    configs
+        .filter(|config| {
+            config.channels() == spec.channels
+                && config.sample_format() == cpal::SampleFormat::F32
+                && config.min_sample_rate().0 <= spec.sample_rate
+                && config.max_sample_rate().0 >= spec.sample_rate
+        })
+        .map(|config| config.with_sample_rate(cpal::SampleRate(spec.sample_rate)).config())
+        .next()
+}
+
+/// Opens an input stream whose callback does nothing but copy samples into a
+/// fresh lock-free ring buffer. The consumer side is handed to the writer
+/// thread through `consumers`.
+fn build_capture_stream(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    recording_flag: &Arc<AtomicBool>,
+    sender: &Sender<RecorderMessage>,
+    consumers: &crossbeam::channel::Sender<rtrb::Consumer<f32>>,
+    dropped_samples: &Arc<AtomicUsize>,
+) -> Result<cpal::Stream, String> {
+    let capacity = config.sample_rate.0 as usize * config.channels as usize * RING_BUFFER_SECONDS;
+    let (mut producer, consumer) = RingBuffer::<f32>::new(capacity);
+
+    let recording_flag = Arc::clone(recording_flag);
+    let dropped_samples = Arc::clone(dropped_samples);
+    let sender = sender.clone();
+    let device_name = device.name().unwrap_or_else(|_| "unknown device".to_string());
+
+    let stream = device
+        .build_input_stream(
+            config,
+            move |data: &[f32], _| {
+                if !recording_flag.load(Ordering::Relaxed) {
+                    return;
+                }
+
+                // Only ever push whole buffers so channels stay interleaved
+                match producer.write_chunk_uninit(data.len()) {
+                    Ok(chunk) => {
+                        chunk.fill_from_iter(data.iter().copied());
+                    }
+                    Err(_) => {
+                        dropped_samples.fetch_add(data.len(), Ordering::Relaxed);
+                    }
+                }
+            },
+            move |err| {
+                log::error!("Error in audio stream: {}", err);
+                if let cpal::StreamError::DeviceNotAvailable = err {
+                    let _ = sender.send(RecorderMessage::DeviceLost(device_name.clone()));
+                }
+            },
+            None,
+        )
+        .map_err(|e| e.to_string())?;
+
+    consumers.send(consumer).map_err(|e| e.to_string())?;
+    stream.play().map_err(|e| e.to_string())?;
+
+    Ok(stream)
+}
+
+/// Captures from `device` into `writer` until a stop message arrives.
+///
+/// The audio callback only pushes into a ring buffer; conversion, denoising
+/// and disk I/O happen on a separate writer thread owned by this function.
+///
+/// If the device disappears or the system default input changes mid-session,
+/// the stream is moved to the new default device when it can produce the same
+/// sample layout. Otherwise capture ends with `CaptureOutcome::DeviceLost`.
+/// Either way the WAV file is finalized before returning.
+pub fn run_capture(
+    device: cpal::Device,
+    config: cpal::StreamConfig,
+    writer: SampleWriter,
+    recording_flag: Arc<AtomicBool>,
+    sender: Sender<RecorderMessage>,
+    receiver: Receiver<RecorderMessage>,
+    on_event: impl Fn(CaptureEvent),
+) -> CaptureOutcome {
+    let host = cpal::default_host();
+    let mut current_device = device.name().unwrap_or_else(|_| "unknown device".to_string());
+
+    let spec = writer.spec();
+    let dropped_samples = Arc::new(AtomicUsize::new(0));
+    let (consumers, consumer_receiver) = crossbeam::channel::unbounded();
+    let writer_thread = std::thread::spawn(move || run_writer(writer, consumer_receiver));
+
+    let initial_stream = build_capture_stream(
+        &device,
+        &config,
+        &recording_flag,
+        &sender,
+        &consumers,
+        &dropped_samples,
+    );
+    let mut stream = match initial_stream {
+        Ok(stream) => Some(stream),
+        Err(e) => {
+            log::error!("Failed to start input stream on {}: {}", current_device, e);
+            None
+        }
+    };
+
+    let outcome = loop {
+        if stream.is_none() {
+            break CaptureOutcome::DeviceLost(current_device.clone());
+        }
+
+        let needs_migration = match receiver.recv_timeout(DEVICE_POLL_INTERVAL) {
+            Ok(RecorderMessage::Stop) | Err(RecvTimeoutError::Disconnected) => {
+                break CaptureOutcome::Stopped;
+            }
+            Ok(RecorderMessage::DeviceLost(name)) => {
+                log::warn!("Input device lost: {}", name);
+                true
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                if !recording_flag.load(Ordering::SeqCst) {
+                    break CaptureOutcome::Stopped;
+                }
+                match default_input_device_name(&host) {
+                    Some(name) if name != current_device => {
+                        log::info!("Default input device changed from {} to {}", current_device, name);
+                        true
+                    }
+                    _ => false,
+                }
+            }
+        };
+
+        if !needs_migration {
+            continue;
+        }
+
+        // Drop the old stream before opening the new one, some backends
+        // refuse to open a second stream while the first is still alive
+        drop(stream.take());
+
+        let Some(new_device) = host.default_input_device() else {
+            log::error!("No input device available to continue recording");
+            break CaptureOutcome::DeviceLost(current_device.clone());
+        };
+        let new_name = new_device.name().unwrap_or_else(|_| "unknown device".to_string());
+
+        let Some(new_config) = matching_input_config(&new_device, &spec) else {
+            log::error!(
+                "Input device {} does not support {} Hz / {} channels, stopping recording",
+                new_name,
+                spec.sample_rate,
+                spec.channels
+            );
+            break CaptureOutcome::DeviceLost(current_device.clone());
+        };
+
+        match build_capture_stream(
+            &new_device,
+            &new_config,
+            &recording_flag,
+            &sender,
+            &consumers,
+            &dropped_samples,
+        ) {
+            Ok(new_stream) => {
+                log::info!("Recording migrated to input device: {}", new_name);
+                stream = Some(new_stream);
+                current_device = new_name.clone();
+                on_event(CaptureEvent::DeviceChanged(new_name));
+            }
+            Err(e) => {
+                log::error!("Failed to start input stream on {}: {}", new_name, e);
+                break CaptureOutcome::DeviceLost(current_device.clone());
+            }
+        }
+    };
+
+    // Dropping the stream and then the consumer channel lets the writer
+    // thread drain what's left and finalize the file
+    drop(stream);
+    drop(consumers);
+    if writer_thread.join().is_err() {
+        log::error!("WAV writer thread panicked");
+    }
+
+    let dropped = dropped_samples.load(Ordering::Relaxed);
+    if dropped > 0 {
+        log::warn!("Dropped {} samples because the writer fell behind", dropped);
+    }
+
+    outcome
+}
+
+/// Same contract as `run_capture`, but samples come from the always-warm
+/// pre-roll stream instead of a freshly opened one. The recording starts with
+/// the buffered pre-roll audio. Device changes are handled by the pre-roll
+/// thread, which ends the recording with `DeviceLost` if the device goes away.
+pub fn run_preroll_capture(
+    attachment: PreRollAttachment,
+    writer: SampleWriter,
+    receiver: Receiver<RecorderMessage>,
+) -> CaptureOutcome {
+    let (consumers, consumer_receiver) = crossbeam::channel::unbounded();
+    let writer_thread = std::thread::spawn(move || run_writer(writer, consumer_receiver));
+
+    log::info!("Recording from pre-roll stream on {}", attachment.device_name);
+
+    let outcome = if consumers.send(attachment.consumer).is_err() {
+        log::error!("WAV writer thread exited before recording started");
+        CaptureOutcome::Stopped
+    } else {
+        match receiver.recv() {
+            Ok(RecorderMessage::Stop) | Err(_) => CaptureOutcome::Stopped,
+            Ok(RecorderMessage::DeviceLost(name)) => {
+                log::warn!("Input device lost: {}", name);
+                CaptureOutcome::DeviceLost(name)
+            }
+        }
+    };
+
+    attachment.link.detach();
+    drop(consumers);
+    if writer_thread.join().is_err() {
+        log::error!("WAV writer thread panicked");
+    }
+
+    outcome
+}
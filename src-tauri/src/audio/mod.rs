@@ -1,4 +1,11 @@
+mod capture;
+mod denoise;
+mod preroll;
 mod recorder;
+pub use capture::*;
+pub use denoise::*;
+pub use preroll::*;
 pub use recorder::*;
+#[cfg(target_os = "macos")]
 pub mod macos;
 pub mod windows;
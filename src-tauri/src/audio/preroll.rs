@@ -0,0 +1,265 @@
+use crate::audio::{default_input_device_name, RecorderMessage};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use rtrb::{Consumer, Producer, RingBuffer};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+// How often the warm thread moves samples out of the callback ring buffer
+const DRAIN_INTERVAL: Duration = Duration::from_millis(10);
+
+// How often the warm thread checks whether the default input device changed
+const DEVICE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// How long to wait before retrying when no input device could be opened
+const REOPEN_BACKOFF: Duration = Duration::from_secs(1);
+
+// Seconds of audio the callback and recording ring buffers can hold
+const RING_BUFFER_SECONDS: usize = 2;
+
+struct Inner {
+    config: Option<(cpal::StreamConfig, String)>,
+    buffer: VecDeque<f32>,
+    capacity: usize,
+    recording: Option<(Producer<f32>, Sender<RecorderMessage>)>,
+}
+
+/// A recording attached to the warm stream. Samples start with the pre-roll
+/// buffer and continue live until `link.detach()` is called.
+pub struct PreRollAttachment {
+    pub consumer: Consumer<f32>,
+    pub config: cpal::StreamConfig,
+    pub device_name: String,
+    pub link: PreRollLink,
+}
+
+pub struct PreRollLink {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl PreRollLink {
+    /// Stops forwarding live samples to the attached recording.
+    pub fn detach(&self) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.recording = None;
+        }
+    }
+}
+
+/// Always-warm, low-latency input stream that keeps the last `duration` of
+/// audio in memory, so a recording can start with what was said just before
+/// the hotkey was pressed.
+///
+/// The stream stays open for as long as this value lives, which keeps the
+/// OS microphone-in-use indicator on the whole time.
+pub struct PreRoll {
+    inner: Arc<Mutex<Inner>>,
+    stop: Sender<()>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl PreRoll {
+    pub fn start(duration: Duration) -> Self {
+        let inner = Arc::new(Mutex::new(Inner {
+            config: None,
+            buffer: VecDeque::new(),
+            capacity: 0,
+            recording: None,
+        }));
+        let (stop, stop_receiver) = channel::<()>();
+
+        let thread_inner = Arc::clone(&inner);
+        let thread = std::thread::spawn(move || {
+            let host = cpal::default_host();
+            let mut stream: Option<(cpal::Stream, Consumer<f32>, Arc<AtomicBool>)> = None;
+            let mut last_device_check = Instant::now();
+
+            loop {
+                match stop_receiver.recv_timeout(DRAIN_INTERVAL) {
+                    Ok(()) | Err(RecvTimeoutError::Disconnected) => break,
+                    Err(RecvTimeoutError::Timeout) => {}
+                }
+
+                if stream.is_none() {
+                    match open_warm_stream(&host, duration, &thread_inner) {
+                        Ok(opened) => stream = Some(opened),
+                        Err(e) => {
+                            log::warn!("Failed to open pre-roll input stream: {}", e);
+                            std::thread::sleep(REOPEN_BACKOFF);
+                            continue;
+                        }
+                    }
+                }
+
+                if let Some((_, consumer, _)) = stream.as_mut() {
+                    forward_samples(consumer, &thread_inner);
+                }
+
+                if last_device_check.elapsed() < DEVICE_POLL_INTERVAL {
+                    continue;
+                }
+                last_device_check = Instant::now();
+
+                let lost = stream
+                    .as_ref()
+                    .map(|(_, _, lost)| lost.load(Ordering::Relaxed))
+                    .unwrap_or(false);
+                let current_name = thread_inner
+                    .lock()
+                    .ok()
+                    .and_then(|inner| inner.config.as_ref().map(|(_, name)| name.clone()));
+                let changed = current_name.is_some() && default_input_device_name(&host) != current_name;
+
+                if !lost && !changed {
+                    continue;
+                }
+
+                // An attached recording can't follow us onto a device with a
+                // different sample layout, so end it and keep what it has
+                if let Ok(mut inner) = thread_inner.lock() {
+                    if let Some((_, sender)) = inner.recording.take() {
+                        let _ = sender.send(RecorderMessage::DeviceLost(current_name.clone().unwrap_or_default()));
+                    }
+                    inner.config = None;
+                    inner.buffer.clear();
+                }
+
+                log::info!("Reopening pre-roll input stream on the default device");
+                stream = None;
+            }
+        });
+
+        Self {
+            inner,
+            stop,
+            thread: Some(thread),
+        }
+    }
+
+    /// Attaches a recording to the warm stream. Fails if the stream isn't
+    /// currently open, in which case the caller should open its own.
+    pub fn attach(&self, sender: Sender<RecorderMessage>) -> Result<PreRollAttachment, String> {
+        let mut inner = self.inner.lock().map_err(|e| e.to_string())?;
+        let (config, device_name) = inner
+            .config
+            .clone()
+            .ok_or_else(|| "Pre-roll input stream is not running".to_string())?;
+
+        let capacity = config.sample_rate.0 as usize * config.channels as usize * RING_BUFFER_SECONDS
+            + inner.capacity;
+        let (mut producer, consumer) = RingBuffer::<f32>::new(capacity);
+
+        let buffered: Vec<f32> = inner.buffer.drain(..).collect();
+        if let Ok(chunk) = producer.write_chunk_uninit(buffered.len()) {
+            chunk.fill_from_iter(buffered.into_iter());
+        }
+
+        inner.recording = Some((producer, sender));
+
+        Ok(PreRollAttachment {
+            consumer,
+            config,
+            device_name,
+            link: PreRollLink {
+                inner: Arc::clone(&self.inner),
+            },
+        })
+    }
+}
+
+impl Drop for PreRoll {
+    fn drop(&mut self) {
+        let _ = self.stop.send(());
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                log::error!("Pre-roll thread panicked");
+            }
+        }
+    }
+}
+
+fn open_warm_stream(
+    host: &cpal::Host,
+    duration: Duration,
+    inner: &Arc<Mutex<Inner>>,
+) -> Result<(cpal::Stream, Consumer<f32>, Arc<AtomicBool>), String> {
+    let device = host
+        .default_input_device()
+        .ok_or_else(|| "No input device available".to_string())?;
+    let device_name = device.name().map_err(|e| e.to_string())?;
+    let config: cpal::StreamConfig = device.default_input_config().map_err(|e| e.to_string())?.into();
+
+    let frame_len = config.channels as usize;
+    let capacity = config.sample_rate.0 as usize * frame_len * RING_BUFFER_SECONDS;
+    let (mut producer, consumer) = RingBuffer::<f32>::new(capacity);
+
+    let lost = Arc::new(AtomicBool::new(false));
+    let lost_flag = Arc::clone(&lost);
+
+    let stream = device
+        .build_input_stream(
+            &config,
+            move |data: &[f32], _| {
+                if let Ok(chunk) = producer.write_chunk_uninit(data.len()) {
+                    chunk.fill_from_iter(data.iter().copied());
+                }
+            },
+            move |err| {
+                log::error!("Error in pre-roll audio stream: {}", err);
+                if let cpal::StreamError::DeviceNotAvailable = err {
+                    lost_flag.store(true, Ordering::Relaxed);
+                }
+            },
+            None,
+        )
+        .map_err(|e| e.to_string())?;
+    stream.play().map_err(|e| e.to_string())?;
+
+    let pre_roll_samples = (config.sample_rate.0 as u128 * duration.as_millis() / 1000) as usize * frame_len;
+
+    let mut inner = inner.lock().map_err(|e| e.to_string())?;
+    inner.capacity = pre_roll_samples;
+    inner.buffer = VecDeque::with_capacity(pre_roll_samples);
+    inner.config = Some((config, device_name.clone()));
+
+    log::info!("Pre-roll input stream running on {}", device_name);
+
+    Ok((stream, consumer, lost))
+}
+
+/// Moves samples from the callback ring into the attached recording, or into
+/// the rolling pre-roll buffer when nothing is recording.
+fn forward_samples(consumer: &mut Consumer<f32>, inner: &Arc<Mutex<Inner>>) {
+    let Ok(mut inner) = inner.lock() else {
+        return;
+    };
+    let frame_len = inner.config.as_ref().map(|(c, _)| c.channels as usize).unwrap_or(1).max(1);
+
+    let available = consumer.slots();
+    let available = available - available % frame_len;
+    if available == 0 {
+        return;
+    }
+    let Ok(chunk) = consumer.read_chunk(available) else {
+        return;
+    };
+    let (first, second) = chunk.as_slices();
+
+    if let Some((producer, _)) = inner.recording.as_mut() {
+        match producer.write_chunk_uninit(available) {
+            Ok(out) => {
+                out.fill_from_iter(first.iter().chain(second.iter()).copied());
+            }
+            Err(_) => log::warn!("Recording fell behind, dropped {} pre-roll samples", available),
+        }
+    } else {
+        inner.buffer.extend(first.iter().chain(second.iter()).copied());
+        let overflow = inner.buffer.len().saturating_sub(inner.capacity);
+        inner.buffer.drain(..overflow);
+    }
+
+    chunk.commit_all();
+}
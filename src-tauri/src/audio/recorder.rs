@@ -1,9 +1,17 @@
+use crate::audio::SpectralDenoiser;
+use crossbeam::channel::{Receiver, TryRecvError};
 use hound::WavSpec;
 use log;
+use rtrb::Consumer;
 use std::fs::File;
 use std::io::BufWriter;
-use std::sync::Arc;
-use std::sync::Mutex;
+use std::time::Duration;
+
+// Gain applied before converting to i16, most laptop mics come in very quiet
+const INPUT_GAIN: f32 = 10.0;
+
+// How long the writer thread sleeps when the ring buffer is empty
+const WRITER_POLL_INTERVAL: Duration = Duration::from_millis(10);
 
 pub fn wav_spec_from_config(config: &cpal::SupportedStreamConfig) -> WavSpec {
     WavSpec {
@@ -14,52 +22,144 @@ pub fn wav_spec_from_config(config: &cpal::SupportedStreamConfig) -> WavSpec {
     }
 }
 
-pub fn write_input_data(
-    input: &[f32],
-    writer: &Arc<Mutex<Option<(hound::WavWriter<BufWriter<File>>, String)>>>,
-) {
-    log::info!("Writing {} samples to WAV file", input.len());
-
-    // Count non-zero samples for debugging
-    let non_zero_count = input.iter().filter(|&&x| x.abs() > 1e-7).count();
-    log::info!("Non-zero samples: {}/{}", non_zero_count, input.len());
-
-    // Preview both raw and amplified values
-    let preview_samples: Vec<(f32, f32)> = input[..5.min(input.len())]
-        .iter()
-        .map(|&x| (x, x * 10.0)) // Show both original and amplified values
-        .collect();
-    log::info!("Sample preview (original, amplified): {:?}", preview_samples);
-
-    match writer.lock() {
-        Ok(mut guard) => {
-            if let Some((writer, _)) = guard.as_mut() {
-                let mut samples_written = 0;
-                for &sample in input.iter() {
-                    // Apply gain to increase volume (adjust multiplier as needed)
-                    let amplified_sample = sample * 10.0; // Increase gain by 10x
-
-                    // Convert to i16 with proper scaling
-                    let converted_sample = (amplified_sample * i16::MAX as f32).clamp(
-                        i16::MIN as f32,
-                        i16::MAX as f32
-                    ) as i16;
-
-                    match writer.write_sample(converted_sample) {
-                        Ok(_) => samples_written += 1,
-                        Err(e) => {
-                            log::error!("Failed to write sample: {}", e);
-                            return;
-                        }
-                    }
+pub fn wav_spec_from_stream_config(config: &cpal::StreamConfig) -> WavSpec {
+    WavSpec {
+        channels: config.channels,
+        sample_rate: config.sample_rate.0,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    }
+}
+
+/// Owns the WAV file for a recording. Lives on the writer thread, so nothing
+/// here ever runs inside the audio callback.
+pub struct SampleWriter {
+    writer: hound::WavWriter<BufWriter<File>>,
+    path: String,
+    denoiser: Option<SpectralDenoiser>,
+    samples_written: usize,
+}
+
+impl SampleWriter {
+    pub fn new(writer: hound::WavWriter<BufWriter<File>>, path: String, noise_suppression: Option<f32>) -> Self {
+        let channels = writer.spec().channels;
+        Self {
+            writer,
+            path,
+            denoiser: noise_suppression.map(|strength| SpectralDenoiser::new(channels, strength)),
+            samples_written: 0,
+        }
+    }
+
+    pub fn spec(&self) -> WavSpec {
+        self.writer.spec()
+    }
+
+    pub fn write(&mut self, input: &[f32]) -> Result<(), hound::Error> {
+        let max_amplitude = input.iter().map(|&x| x.abs()).fold(0.0f32, f32::max);
+        if max_amplitude <= 0.00001 {
+            return Ok(());
+        }
+
+        match self.denoiser.as_mut() {
+            Some(denoiser) => {
+                let denoised = denoiser.process(input);
+                self.write_converted(&denoised)
+            }
+            None => self.write_converted(input),
+        }
+    }
+
+    fn write_converted(&mut self, input: &[f32]) -> Result<(), hound::Error> {
+        for &sample in input {
+            let amplified_sample = sample * INPUT_GAIN;
+
+            // Convert to i16 with proper scaling
+            let converted_sample =
+                (amplified_sample * i16::MAX as f32).clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+
+            self.writer.write_sample(converted_sample)?;
+            self.samples_written += 1;
+        }
+        Ok(())
+    }
+
+    /// Flushes the denoiser tail and finalizes the WAV header.
+    pub fn finalize(mut self) -> Result<(), hound::Error> {
+        if let Some(mut denoiser) = self.denoiser.take() {
+            let tail = denoiser.flush();
+            self.write_converted(&tail)?;
+        }
+
+        log::info!("Wrote {} samples to {}", self.samples_written, self.path);
+        self.writer.finalize()
+    }
+}
+
+/// Reads everything currently in the ring buffer, in whole frames so channels
+/// stay interleaved correctly, and hands it to the WAV writer.
+fn drain_ring(consumer: &mut Consumer<f32>, writer: &mut SampleWriter, channels: usize) -> usize {
+    let available = consumer.slots();
+    let available = available - available % channels;
+    if available == 0 {
+        return 0;
+    }
+
+    match consumer.read_chunk(available) {
+        Ok(chunk) => {
+            let (first, second) = chunk.as_slices();
+            for slice in [first, second] {
+                if let Err(e) = writer.write(slice) {
+                    log::error!("Failed to write samples: {}", e);
                 }
-                log::info!("Successfully wrote {} samples (after amplification)", samples_written);
-            } else {
-                log::error!("WAV writer is not available");
             }
+            chunk.commit_all();
+            available
         }
         Err(e) => {
-            log::error!("Failed to acquire lock on WAV writer: {}", e);
+            log::error!("Failed to read from ring buffer: {}", e);
+            0
         }
     }
 }
+
+/// Body of the writer thread. Each input stream gets its own ring buffer, the
+/// capture thread sends the consumer side over `consumers` whenever it opens a
+/// new stream and drops the sender once the recording is over.
+pub fn run_writer(mut writer: SampleWriter, consumers: Receiver<Consumer<f32>>) {
+    let channels = writer.spec().channels.max(1) as usize;
+    let mut current: Option<Consumer<f32>> = None;
+
+    loop {
+        let drained = current
+            .as_mut()
+            .map(|consumer| drain_ring(consumer, &mut writer, channels))
+            .unwrap_or(0);
+
+        match consumers.try_recv() {
+            Ok(next) => {
+                // Finish the old stream's samples before switching over
+                if let Some(consumer) = current.as_mut() {
+                    drain_ring(consumer, &mut writer, channels);
+                }
+                current = Some(next);
+                continue;
+            }
+            Err(TryRecvError::Disconnected) => {
+                if let Some(consumer) = current.as_mut() {
+                    drain_ring(consumer, &mut writer, channels);
+                }
+                break;
+            }
+            Err(TryRecvError::Empty) => {}
+        }
+
+        if drained == 0 {
+            std::thread::sleep(WRITER_POLL_INTERVAL);
+        }
+    }
+
+    if let Err(e) = writer.finalize() {
+        log::error!("Failed to finalize WAV file: {}", e);
+    }
+}
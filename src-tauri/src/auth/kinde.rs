@@ -0,0 +1,59 @@
+use crate::error::AppError;
+use serde::Deserialize;
+use std::time::Duration;
+
+// Same application the frontend signs in with, see `KindeProvider` in main.tsx
+pub const CLIENT_ID: &str = "98c15f1315d845e69890b864f3fb842e";
+pub const TOKEN_ENDPOINT: &str = "https://jeffai.kinde.com/oauth2/token";
+
+// Refreshes run in the background, but a hung one would hold the session lock
+const REFRESH_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Deserialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    /// Kinde rotates refresh tokens, when present this replaces the old one
+    pub refresh_token: Option<String>,
+    pub expires_in: Option<i64>,
+}
+
+/// Exchanges a refresh token for a new access token. The app is a public
+/// PKCE client, so no client secret is sent.
+pub async fn refresh_access_token(http: &reqwest::Client, refresh_token: &str) -> Result<TokenResponse, AppError> {
+    let response = http
+        .post(TOKEN_ENDPOINT)
+        .timeout(REFRESH_TIMEOUT)
+        .form(&[
+            ("grant_type", "refresh_token"),
+            ("client_id", CLIENT_ID),
+            ("refresh_token", refresh_token),
+        ])
+        .send()
+        .await
+        .map_err(|e| {
+            if e.is_timeout() {
+                AppError::Timeout
+            } else {
+                AppError::Network(e.to_string())
+            }
+        })?;
+
+    let status = response.status();
+    if status.is_client_error() {
+        // Revoked or expired refresh token, the user has to sign in again
+        log::warn!("Token refresh rejected with {}", status);
+        return Err(AppError::Unauthenticated);
+    }
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(AppError::Server {
+            status: status.as_u16(),
+            body,
+        });
+    }
+
+    response
+        .json::<TokenResponse>()
+        .await
+        .map_err(|e| AppError::InvalidResponse(e.to_string()))
+}
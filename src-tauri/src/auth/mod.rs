@@ -0,0 +1,7 @@
+mod kinde;
+mod session;
+mod store;
+
+pub use kinde::refresh_access_token;
+pub use session::{spawn_refresh_loop, Session, SessionStore, SESSION_SECRET};
+pub use store::SecretStore;
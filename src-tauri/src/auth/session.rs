@@ -0,0 +1,239 @@
+use crate::auth::{refresh_access_token, SecretStore};
+use crate::error::AppError;
+use crate::state::AppState;
+use crate::AppHandle;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tauri::{Emitter, EventTarget, Manager};
+
+// Tokens are refreshed when they have less than this many seconds left, long
+// enough to cover uploading and transcribing a long dictation
+const REFRESH_MARGIN_SECS: i64 = 5 * 60;
+
+// Name of the session entry in the secret store
+pub const SESSION_SECRET: &str = "session";
+
+// How often the background task checks whether the token needs refreshing
+const REFRESH_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Kinde tokens for the signed-in user.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+// Keep tokens out of logs
+impl std::fmt::Debug for Session {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Session")
+            .field("has_refresh_token", &self.refresh_token.is_some())
+            .field("expires_at", &self.expires_at)
+            .finish()
+    }
+}
+
+impl Session {
+    /// Builds a session, taking the expiry from `expires_in` or, failing
+    /// that, from the access token's `exp` claim.
+    pub fn new(access_token: String, refresh_token: Option<String>, expires_in: Option<i64>) -> Self {
+        let expires_at = expires_in
+            .map(|seconds| Utc::now() + Duration::seconds(seconds))
+            .or_else(|| jwt_expiry(&access_token))
+            .unwrap_or_else(Utc::now);
+
+        Self {
+            access_token,
+            refresh_token,
+            expires_at,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+
+    fn expires_soon(&self) -> bool {
+        self.expires_at - Duration::seconds(REFRESH_MARGIN_SECS) <= Utc::now()
+    }
+}
+
+/// Reads the `exp` claim without verifying the signature. The backend does
+/// the verifying, this is only used to schedule refreshes.
+fn jwt_expiry(token: &str) -> Option<DateTime<Utc>> {
+    let payload = token.split('.').nth(1)?;
+    let bytes = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    let claims: Value = serde_json::from_slice(&bytes).ok()?;
+    DateTime::from_timestamp(claims.get("exp")?.as_i64()?, 0)
+}
+
+/// Holds the current session and keeps the persisted copy in sync with it.
+/// The async mutex is held across a refresh, so concurrent requests wait for
+/// one refresh instead of each starting their own.
+pub struct SessionStore {
+    session: tokio::sync::Mutex<Option<Session>>,
+    secrets: std::sync::Mutex<Arc<SecretStore>>,
+}
+
+impl SessionStore {
+    /// Restores the session saved by a previous run, if any.
+    pub fn restore(secrets: Arc<SecretStore>) -> Self {
+        let session = secrets.load::<Session>(SESSION_SECRET);
+        if let Some(session) = session.as_ref() {
+            log::info!("Restored session: {:?}", session);
+        }
+
+        Self {
+            session: tokio::sync::Mutex::new(session),
+            secrets: std::sync::Mutex::new(secrets),
+        }
+    }
+
+    /// Swaps in another profile's secret store and its saved session.
+    pub async fn switch(&self, secrets: Arc<SecretStore>) {
+        let mut guard = self.session.lock().await;
+        *guard = secrets.load::<Session>(SESSION_SECRET);
+        if let Ok(mut current) = self.secrets.lock() {
+            *current = secrets;
+        }
+    }
+
+    fn secrets(&self) -> Option<Arc<SecretStore>> {
+        self.secrets.lock().ok().map(|secrets| Arc::clone(&secrets))
+    }
+
+    pub async fn set(&self, session: Session) {
+        log::info!("Session updated: {:?}", session);
+        self.persist(&session);
+        *self.session.lock().await = Some(session);
+    }
+
+    pub async fn clear(&self) {
+        self.session.lock().await.take();
+        if let Some(secrets) = self.secrets() {
+            secrets.delete(SESSION_SECRET);
+        }
+    }
+
+    fn persist(&self, session: &Session) {
+        let Some(secrets) = self.secrets() else {
+            return;
+        };
+        if let Err(e) = secrets.save(SESSION_SECRET, session) {
+            log::error!("Failed to persist session: {}", e);
+        }
+    }
+
+    pub async fn get(&self) -> Option<Session> {
+        self.session.lock().await.clone()
+    }
+
+    /// Returns a usable access token, refreshing it first if it expires within
+    /// the refresh margin.
+    pub async fn access_token(&self, http: &reqwest::Client) -> Result<String, AppError> {
+        let mut guard = self.session.lock().await;
+        let session = guard.as_mut().ok_or(AppError::Unauthenticated)?;
+
+        if session.expires_soon() {
+            if refresh(session, http).await? {
+                self.persist(session);
+            }
+        }
+
+        Ok(session.access_token.clone())
+    }
+}
+
+/// Refreshes `session` in place, returning whether it changed.
+async fn refresh(session: &mut Session, http: &reqwest::Client) -> Result<bool, AppError> {
+    let Some(refresh_token) = session.refresh_token.clone() else {
+        return if session.is_expired() {
+            Err(AppError::Unauthenticated)
+        } else {
+            Ok(false)
+        };
+    };
+
+    match refresh_access_token(http, &refresh_token).await {
+        Ok(tokens) => {
+            *session = Session::new(
+                tokens.access_token,
+                tokens.refresh_token.or(Some(refresh_token)),
+                tokens.expires_in,
+            );
+            log::info!("Refreshed access token, expires at {}", session.expires_at);
+            Ok(true)
+        }
+        // A failed refresh is only fatal once the current token is unusable
+        Err(e) if !session.is_expired() && e.retryable() => {
+            log::warn!("Failed to refresh access token, will retry: {}", e);
+            Ok(false)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Keeps the access token fresh in the background, so a long dictation never
+/// starts its upload with a token that's about to expire.
+///
+/// When Kinde rotates the refresh token, the new one goes to the frontend as
+/// `session-refreshed` so the Kinde SDK restores the next launch from it
+/// rather than from the one that was just used up. Only a session saved
+/// before refresh tokens were handed over lacks one, for those the frontend
+/// gets `session-refresh-needed` and is expected to call `set_session` again.
+/// `session-expired` means the user has to sign in again.
+pub fn spawn_refresh_loop(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        // Whatever the session was restored with came from the frontend
+        let mut known_refresh_token = app_handle
+            .state::<AppState>()
+            .session
+            .get()
+            .await
+            .and_then(|session| session.refresh_token);
+
+        loop {
+            tokio::time::sleep(REFRESH_CHECK_INTERVAL).await;
+
+            let state = app_handle.state::<AppState>();
+            let Some(session) = state.session.get().await else {
+                known_refresh_token = None;
+                continue;
+            };
+
+            if session.refresh_token.is_none() {
+                if session.expires_soon() {
+                    let _ = app_handle.emit_to(EventTarget::any(), "session-refresh-needed", ());
+                }
+                continue;
+            }
+
+            // The refresh token the frontend was last known to hold
+            let handed_over = known_refresh_token.take().or_else(|| session.refresh_token.clone());
+
+            if session.expires_soon() {
+                match state.session.access_token(&state.http_client).await {
+                    Ok(_) => {}
+                    Err(AppError::Unauthenticated) => {
+                        state.session.clear().await;
+                        let _ = app_handle.emit_to(EventTarget::any(), "session-expired", ());
+                        continue;
+                    }
+                    Err(e) => log::warn!("Background token refresh failed: {}", e),
+                }
+            }
+
+            // Requests refresh the session too, not only the check above
+            let refresh_token = state.session.get().await.and_then(|session| session.refresh_token);
+            if refresh_token.is_some() && refresh_token != handed_over {
+                let _ = app_handle.emit_to(EventTarget::any(), "session-refreshed", refresh_token.clone());
+            }
+            known_refresh_token = refresh_token;
+        }
+    });
+}
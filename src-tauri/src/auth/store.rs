@@ -0,0 +1,219 @@
+use crate::profile::ProfilePaths;
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use rand::RngCore;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Keyring service name, same as the bundle identifier
+const SERVICE: &str = "com.jeff-ai.app";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Stores small secrets like the session tokens in the OS credential store:
+/// Keychain on macOS, Credential Manager on Windows and the Secret Service
+/// (GNOME Keyring, KWallet) on Linux.
+///
+/// Entries are namespaced per profile, so each account keeps its own session.
+/// The default profile also picks up entries saved before profiles existed,
+/// moving them under its namespace the first time they're read.
+///
+/// When no credential store is available, e.g. a Linux desktop without a
+/// Secret Service provider, values are written to `<name>.enc` files
+/// encrypted with a key derived from the machine ID. That only stops the
+/// file from being usable on another machine, anyone who can run code as the
+/// user can still decrypt it.
+pub struct SecretStore {
+    namespace: String,
+    fallback_dir: PathBuf,
+    legacy_dir: Option<PathBuf>,
+}
+
+impl SecretStore {
+    pub fn for_profile(paths: &ProfilePaths) -> Self {
+        Self {
+            namespace: paths.id.clone(),
+            fallback_dir: paths.config_dir.clone(),
+            legacy_dir: paths.legacy_dir.clone(),
+        }
+    }
+
+    fn entry(&self, name: &str) -> keyring::Result<keyring::Entry> {
+        keyring::Entry::new(SERVICE, &format!("{}/{}", self.namespace, name))
+    }
+
+    pub fn save<T: Serialize>(&self, name: &str, value: &T) -> Result<(), String> {
+        let json = serde_json::to_string(value).map_err(|e| e.to_string())?;
+        self.store(name, &json)
+    }
+
+    fn store(&self, name: &str, json: &str) -> Result<(), String> {
+        let fallback_path = self.fallback_path(name);
+
+        match self.entry(name).and_then(|entry| entry.set_password(json)) {
+            Ok(()) => {
+                // Don't leave an older copy behind once the keyring works
+                if fallback_path.exists() {
+                    let _ = fs::remove_file(&fallback_path);
+                }
+                Ok(())
+            }
+            Err(e) => {
+                log::warn!("Credential store unavailable, using encrypted file: {}", e);
+                write_encrypted(&fallback_path, json.as_bytes())
+            }
+        }
+    }
+
+    pub fn load<T: DeserializeOwned>(&self, name: &str) -> Option<T> {
+        let json = match self.entry(name).and_then(|entry| entry.get_password()) {
+            Ok(json) => json,
+            Err(keyring::Error::NoEntry) => self.load_fallback(name).or_else(|| self.load_legacy(name))?,
+            Err(e) => {
+                log::warn!("Failed to read from credential store: {}", e);
+                self.load_fallback(name).or_else(|| self.load_legacy(name))?
+            }
+        };
+
+        serde_json::from_str(&json)
+            .map_err(|e| log::warn!("Discarding unreadable {}: {}", name, e))
+            .ok()
+    }
+
+    pub fn delete(&self, name: &str) {
+        match self.entry(name).and_then(|entry| entry.delete_credential()) {
+            Ok(()) | Err(keyring::Error::NoEntry) => {}
+            Err(e) => log::warn!("Failed to delete {} from credential store: {}", name, e),
+        }
+
+        remove_fallback(&self.fallback_path(name));
+        self.delete_legacy(name);
+    }
+
+    fn fallback_path(&self, name: &str) -> PathBuf {
+        self.fallback_dir.join(format!("{}.enc", name))
+    }
+
+    fn load_fallback(&self, name: &str) -> Option<String> {
+        let path = self.fallback_path(name);
+        if !path.exists() {
+            return None;
+        }
+
+        read_encrypted(&path)
+            .map_err(|e| log::warn!("Failed to read {:?}: {}", path, e))
+            .ok()
+    }
+
+    // Before profiles, entries were stored under their bare name and files
+    // straight in the app config dir
+    fn load_legacy(&self, name: &str) -> Option<String> {
+        let legacy_dir = self.legacy_dir.as_ref()?;
+        let json = match keyring::Entry::new(SERVICE, name).and_then(|entry| entry.get_password()) {
+            Ok(json) => Some(json),
+            Err(keyring::Error::NoEntry) => None,
+            Err(e) => {
+                log::warn!("Failed to read {} from credential store: {}", name, e);
+                None
+            }
+        };
+        let json = json.or_else(|| {
+            let path = legacy_dir.join(format!("{}.enc", name));
+            if !path.exists() {
+                return None;
+            }
+            read_encrypted(&path)
+                .map_err(|e| log::warn!("Failed to read {:?}: {}", path, e))
+                .ok()
+        })?;
+
+        log::info!("Moving {} into profile {}", name, self.namespace);
+        match self.store(name, &json) {
+            Ok(()) => self.delete_legacy(name),
+            Err(e) => log::warn!("Failed to move {} into profile {}: {}", name, self.namespace, e),
+        }
+        Some(json)
+    }
+
+    fn delete_legacy(&self, name: &str) {
+        let Some(legacy_dir) = &self.legacy_dir else {
+            return;
+        };
+        match keyring::Entry::new(SERVICE, name).and_then(|entry| entry.delete_credential()) {
+            Ok(()) | Err(keyring::Error::NoEntry) => {}
+            Err(e) => log::warn!("Failed to delete {} from credential store: {}", name, e),
+        }
+        remove_fallback(&legacy_dir.join(format!("{}.enc", name)));
+    }
+}
+
+fn remove_fallback(path: &Path) {
+    if path.exists() {
+        if let Err(e) = fs::remove_file(path) {
+            log::warn!("Failed to delete {:?}: {}", path, e);
+        }
+    }
+}
+
+fn derive_key(salt: &[u8]) -> Result<Key<Aes256Gcm>, String> {
+    let machine_id = machine_uid::get().map_err(|e| format!("Failed to read machine ID: {}", e))?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(SERVICE.as_bytes());
+    hasher.update(machine_id.as_bytes());
+    hasher.update(salt);
+
+    Ok(hasher.finalize())
+}
+
+// File layout: salt, nonce, then the AES-256-GCM ciphertext
+fn write_encrypted(path: &Path, plaintext: &[u8]) -> Result<(), String> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let cipher = Aes256Gcm::new(&derive_key(&salt)?);
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plaintext)
+        .map_err(|e| format!("Failed to encrypt: {}", e))?;
+
+    let mut contents = Vec::with_capacity(SALT_LEN + NONCE_LEN + ciphertext.len());
+    contents.extend_from_slice(&salt);
+    contents.extend_from_slice(&nonce);
+    contents.extend_from_slice(&ciphertext);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(path, contents).map_err(|e| e.to_string())?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600)).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+fn read_encrypted(path: &Path) -> Result<String, String> {
+    let contents = fs::read(path).map_err(|e| e.to_string())?;
+    if contents.len() < SALT_LEN + NONCE_LEN {
+        return Err("File is truncated".to_string());
+    }
+
+    let (salt, rest) = contents.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    let cipher = Aes256Gcm::new(&derive_key(salt)?);
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Failed to decrypt, the file may be from another machine".to_string())?;
+
+    String::from_utf8(plaintext).map_err(|e| e.to_string())
+}
@@ -0,0 +1,82 @@
+use clipboard_rs::{Clipboard, ClipboardContent, ClipboardContext};
+
+/// Every format that was on the clipboard before we overwrote it, so images,
+/// rich text and file references survive a paste. Formats the platform won't
+/// hand over as raw bytes (e.g. Windows GDI bitmaps) are skipped.
+pub struct ClipboardSnapshot {
+    contents: Vec<ClipboardContent>,
+}
+
+impl ClipboardSnapshot {
+    pub fn capture() -> Option<Self> {
+        let context = ClipboardContext::new()
+            .map_err(|e| log::error!("Failed to open clipboard: {}", e))
+            .ok()?;
+        let formats = context
+            .available_formats()
+            .map_err(|e| log::error!("Failed to list clipboard formats: {}", e))
+            .ok()?;
+
+        let contents = formats
+            .into_iter()
+            .filter_map(|format| match context.get_buffer(&format) {
+                Ok(buffer) => Some(ClipboardContent::Other(format, buffer)),
+                Err(e) => {
+                    log::warn!("Skipping clipboard format {}: {}", format, e);
+                    None
+                }
+            })
+            .collect();
+
+        Some(Self { contents })
+    }
+
+    pub fn restore(self) {
+        let context = match ClipboardContext::new() {
+            Ok(context) => context,
+            Err(e) => {
+                log::error!("Failed to open clipboard: {}", e);
+                return;
+            }
+        };
+
+        let result = if self.contents.is_empty() {
+            context.clear()
+        } else {
+            context.set(self.contents)
+        };
+        if let Err(e) = result {
+            log::error!("Error restoring clipboard: {}", e);
+        }
+    }
+}
+
+/// A counter the OS bumps every time the clipboard is written. Used to tell
+/// whether someone else wrote to the clipboard after we did.
+#[cfg(target_os = "macos")]
+pub fn change_count() -> Option<i64> {
+    use objc::runtime::Object;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    unsafe {
+        let pasteboard: *mut Object = msg_send![class!(NSPasteboard), generalPasteboard];
+        if pasteboard.is_null() {
+            return None;
+        }
+        let count: isize = msg_send![pasteboard, changeCount];
+        Some(count as i64)
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn change_count() -> Option<i64> {
+    use windows_sys::Win32::System::DataExchange::GetClipboardSequenceNumber;
+
+    let count = unsafe { GetClipboardSequenceNumber() };
+    (count != 0).then_some(count as i64)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn change_count() -> Option<i64> {
+    None
+}
@@ -0,0 +1,207 @@
+use chrono::{DateTime, Local};
+use fs2::FileExt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Resolves a journal path pattern for `now`. `YYYY`, `MM` and `DD` in the
+/// file name are replaced with the local date, so `notes/YYYY-MM-DD.md` gives
+/// one file per day. They're only replaced where they stand alone or next to
+/// each other, `SUMMARY.md` stays as it is, and never in directory names. `~`
+/// expands to the home directory and relative paths are taken from
+/// `base_dir`.
+pub fn resolve_journal_path(pattern: &str, now: &DateTime<Local>, base_dir: &Path) -> PathBuf {
+    let name_start = pattern.rfind(['/', '\\']).map_or(0, |i| i + 1);
+    let (dir, name) = pattern.split_at(name_start);
+    let resolved = format!("{}{}", dir, replace_date_tokens(name, now));
+
+    let path = match resolved.strip_prefix("~/") {
+        Some(rest) => std::env::var_os("HOME")
+            .or_else(|| std::env::var_os("USERPROFILE"))
+            .map(|home| PathBuf::from(home).join(rest))
+            .unwrap_or_else(|| PathBuf::from(&resolved)),
+        None => PathBuf::from(&resolved),
+    };
+
+    if path.is_absolute() {
+        path
+    } else {
+        base_dir.join(path)
+    }
+}
+
+// Replaces every run of letters made up only of `YYYY`, `MM` and `DD`
+fn replace_date_tokens(name: &str, now: &DateTime<Local>) -> String {
+    let mut resolved = String::with_capacity(name.len());
+    let mut rest = name;
+
+    while !rest.is_empty() {
+        let run_len = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+        if run_len == 0 {
+            let c = rest.chars().next().unwrap_or_default();
+            resolved.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+
+        let (run, tail) = rest.split_at(run_len);
+        resolved.push_str(&date_run(run, now).unwrap_or_else(|| run.to_string()));
+        rest = tail;
+    }
+
+    resolved
+}
+
+fn date_run(run: &str, now: &DateTime<Local>) -> Option<String> {
+    let mut formatted = String::new();
+    let mut rest = run;
+    while !rest.is_empty() {
+        let (token, format) = [("YYYY", "%Y"), ("MM", "%m"), ("DD", "%d")]
+            .into_iter()
+            .find(|(token, _)| rest.starts_with(token))?;
+        formatted.push_str(&now.format(format).to_string());
+        rest = &rest[token.len()..];
+    }
+    Some(formatted)
+}
+
+/// Appends `text` to the Markdown file at `path` under a timestamp heading.
+///
+/// The entry goes on the end of the existing file in a single write, so the
+/// file keeps its permissions and a symlinked journal (say, into a synced
+/// notes vault) stays a symlink. A sidecar `.lock` file is held exclusively
+/// for the duration, which keeps two dictations (or two app instances) from
+/// interleaving their writes.
+pub fn append_entry(path: &Path, text: &str, now: &DateTime<Local>) -> Result<(), String> {
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create journal directory: {}", e))?;
+
+    let mut lock_path = path.as_os_str().to_owned();
+    lock_path.push(".lock");
+    let lock_file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .map_err(|e| format!("Failed to open journal lock: {}", e))?;
+    lock_file
+        .lock_exclusive()
+        .map_err(|e| format!("Failed to lock journal: {}", e))?;
+
+    let result = (|| {
+        let mut journal = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Failed to open journal: {}", e))?;
+
+        let mut entry = separator(&mut journal).map_err(|e| format!("Failed to read journal: {}", e))?;
+        entry.push_str(&format!("## {}\n\n{}\n", now.format("%Y-%m-%d %H:%M"), text.trim()));
+
+        journal
+            .write_all(entry.as_bytes())
+            .and_then(|_| journal.sync_all())
+            .map_err(|e| format!("Failed to write journal: {}", e))
+    })();
+
+    if let Err(e) = FileExt::unlock(&lock_file) {
+        log::warn!("Failed to unlock journal: {}", e);
+    }
+
+    result
+}
+
+// What goes before a new entry so it's separated from the last one by a blank
+// line, going by how the file currently ends
+fn separator(journal: &mut File) -> std::io::Result<String> {
+    let len = journal.metadata()?.len();
+    if len == 0 {
+        return Ok(String::new());
+    }
+
+    let mut tail = [0u8; 2];
+    let tail_len = len.min(2) as usize;
+    journal.seek(SeekFrom::End(-(tail_len as i64)))?;
+    journal.read_exact(&mut tail[..tail_len])?;
+
+    Ok(match &tail[..tail_len] {
+        b"\n\n" => "",
+        [.., b'\n'] => "\n",
+        _ => "\n\n",
+    }
+    .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 3, 7, 9, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn date_tokens_in_the_file_name_are_replaced() {
+        let base = Path::new("/docs");
+        assert_eq!(
+            resolve_journal_path("notes/YYYY-MM-DD.md", &now(), base),
+            base.join("notes/2026-03-07.md")
+        );
+        assert_eq!(
+            resolve_journal_path("/journal/YYYYMMDD.md", &now(), base),
+            PathBuf::from("/journal/20260307.md")
+        );
+    }
+
+    #[test]
+    fn words_made_of_more_than_tokens_are_left_alone() {
+        let base = Path::new("/docs");
+        assert_eq!(
+            resolve_journal_path("/ADDONS/MM/SUMMARY-YYYY-MM.md", &now(), base),
+            PathBuf::from("/ADDONS/MM/SUMMARY-2026-03.md")
+        );
+        assert_eq!(
+            resolve_journal_path("journal.md", &now(), base),
+            base.join("journal.md")
+        );
+    }
+
+    #[test]
+    fn entries_are_separated_by_a_blank_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.md");
+        fs::write(&path, "# Journal").unwrap();
+
+        append_entry(&path, " first ", &now()).unwrap();
+        append_entry(&path, "second", &now()).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "# Journal\n\n## 2026-03-07 09:30\n\nfirst\n\n## 2026-03-07 09:30\n\nsecond\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_journals_keep_their_link_and_permissions() {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("vault.md");
+        fs::write(&target, "").unwrap();
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o644)).unwrap();
+        let link = dir.path().join("journal.md");
+        symlink(&target, &link).unwrap();
+
+        append_entry(&link, "hello", &now()).unwrap();
+
+        assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(fs::metadata(&target).unwrap().permissions().mode() & 0o777, 0o644);
+        assert_eq!(fs::read_to_string(&target).unwrap(), "## 2026-03-07 09:30\n\nhello\n");
+    }
+}
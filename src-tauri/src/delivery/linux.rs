@@ -0,0 +1,84 @@
+use std::io::ErrorKind;
+use std::process::{Command, Stdio};
+
+/// Wayland has no XTest equivalent, so rdev's simulated key events only reach
+/// XWayland windows there. Detect it so we can fall back to external tools.
+pub fn is_wayland() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_some()
+        || std::env::var("XDG_SESSION_TYPE")
+            .map(|session| session == "wayland")
+            .unwrap_or(false)
+}
+
+/// Runs the first command whose binary is installed. `wtype` works on
+/// compositors with the virtual-keyboard protocol (Sway, Hyprland, KDE),
+/// `ydotool` works everywhere but needs the `ydotoold` daemon running.
+fn run_first(commands: &[Vec<String>]) {
+    // Tools that are installed but didn't manage to send the keys
+    let mut failures = Vec::new();
+
+    for command in commands {
+        let Some((program, args)) = command.split_first() else {
+            continue;
+        };
+
+        match Command::new(program).args(args).stdin(Stdio::null()).output() {
+            Ok(output) if output.status.success() => return,
+            Ok(output) => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let failure = match stderr.trim() {
+                    "" => format!("{} exited with {}", program, output.status),
+                    stderr => format!("{} exited with {}: {}", program, output.status, stderr),
+                };
+                log::warn!("{}", failure);
+                failures.push(failure);
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => {
+                let failure = format!("Failed to run {}: {}", program, e);
+                log::warn!("{}", failure);
+                failures.push(failure);
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        log::error!("Text delivery on Wayland needs wtype or ydotool installed");
+    } else {
+        log::error!("Text delivery on Wayland failed: {}", failures.join("; "));
+    }
+}
+
+pub fn paste() {
+    run_first(&[
+        ["wtype", "-M", "ctrl", "-k", "v", "-m", "ctrl"]
+            .map(String::from)
+            .to_vec(),
+        // Linux input event codes: 29 = left ctrl, 47 = v
+        ["ydotool", "key", "29:1", "47:1", "47:0", "29:0"]
+            .map(String::from)
+            .to_vec(),
+    ]);
+}
+
+pub fn type_text(text: &str, chars_per_second: u32) {
+    let delay_ms = (1000 / chars_per_second.max(1)).to_string();
+
+    run_first(&[
+        vec![
+            "wtype".to_string(),
+            "-d".to_string(),
+            delay_ms.clone(),
+            "--".to_string(),
+            text.to_string(),
+        ],
+        vec![
+            "ydotool".to_string(),
+            "type".to_string(),
+            "--key-delay".to_string(),
+            delay_ms,
+            "--".to_string(),
+            text.to_string(),
+        ],
+    ]);
+}
@@ -0,0 +1,141 @@
+mod clipboard;
+mod journal;
+#[cfg(target_os = "linux")]
+mod linux;
+mod paste;
+mod typing;
+
+pub use journal::{append_entry, resolve_journal_path};
+pub use paste::simulate_paste;
+pub use typing::type_text;
+
+use crate::models::{DeliveryMode, DeliveryPreferences, OutputRoute};
+use crate::state::AppState;
+use crate::AppHandle;
+use clipboard::{change_count, ClipboardSnapshot};
+use chrono::Local;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, EventTarget, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+// How often the clipboard change count is checked while waiting to restore
+const CHANGE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Which transcription a piece of text came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptKind {
+    Raw,
+    Refined,
+}
+
+impl TranscriptKind {
+    /// Event emitted to the frontend for this kind of transcript.
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            TranscriptKind::Raw => "transcription-complete",
+            TranscriptKind::Refined => "refined-transcription-complete",
+        }
+    }
+}
+
+/// Sends a finished transcript wherever the preferences route its kind. The
+/// frontend event is emitted by the caller regardless of the route. Blocks
+/// while keystrokes are simulated, so call it off the async runtime.
+pub fn route_transcript(app_handle: &AppHandle, kind: TranscriptKind, text: &str) {
+    let delivery = {
+        let state = app_handle.state::<AppState>();
+        let preferences = match state.preferences.lock() {
+            Ok(preferences) => preferences,
+            Err(e) => {
+                log::error!("Failed to read preferences: {}", e);
+                return;
+            }
+        };
+        preferences.delivery.clone()
+    };
+
+    let route = match kind {
+        TranscriptKind::Raw => delivery.routes.raw,
+        TranscriptKind::Refined => delivery.routes.refined,
+    };
+    log::info!("Routing {:?} transcript to {:?}", kind, route);
+
+    match route {
+        OutputRoute::Paste => match delivery.mode {
+            DeliveryMode::Paste => paste_text(app_handle, text, &delivery),
+            DeliveryMode::Type => type_text(text, delivery.typing_chars_per_second),
+        },
+        OutputRoute::ClipboardOnly => {
+            if let Err(e) = app_handle.clipboard().write_text(text) {
+                log::error!("Error writing to clipboard: {:?}", e);
+            }
+        }
+        OutputRoute::AppendToFile => match delivery.journal_path.as_deref() {
+            Some(pattern) => match append_to_journal(app_handle, pattern, text) {
+                Ok(path) => {
+                    let _ = app_handle.emit_to(
+                        EventTarget::any(),
+                        "journal-entry-appended",
+                        Some(path.to_string_lossy().to_string()),
+                    );
+                }
+                Err(e) => log::error!("Failed to append transcript to journal: {}", e),
+            },
+            None => log::warn!("No journal file configured, dropping transcript"),
+        },
+        OutputRoute::EventOnly => {}
+    }
+}
+
+/// Appends `text` to the journal file `pattern` resolves to today, relative
+/// paths are taken from the user's documents folder. Returns the file written.
+pub fn append_to_journal(app_handle: &AppHandle, pattern: &str, text: &str) -> Result<PathBuf, String> {
+    let now = Local::now();
+    let base_dir = app_handle.path().document_dir().map_err(|e| e.to_string())?;
+    let path = resolve_journal_path(pattern, &now, &base_dir);
+
+    append_entry(&path, text, &now)?;
+    log::info!("Appended transcript to journal: {:?}", path);
+
+    Ok(path)
+}
+
+/// Pastes `text` through the clipboard, then puts the previous clipboard
+/// contents back in every format they were in.
+///
+/// The restore waits `clipboard_restore_delay_ms` so the target app has time
+/// to read the clipboard. If anything else writes to the clipboard while we
+/// wait, the restore is skipped rather than clobbering it.
+fn paste_text(app_handle: &AppHandle, text: &str, delivery: &DeliveryPreferences) {
+    let snapshot = if delivery.leave_on_clipboard {
+        None
+    } else {
+        ClipboardSnapshot::capture()
+    };
+
+    if let Err(e) = app_handle.clipboard().write_text(text) {
+        log::error!("Error writing to clipboard: {:?}", e);
+        return;
+    }
+    let written_count = change_count();
+
+    // paste the text
+    simulate_paste();
+
+    let Some(snapshot) = snapshot else {
+        return;
+    };
+
+    let delay = Duration::from_millis(delivery.clipboard_restore_delay_ms as u64);
+    let started = Instant::now();
+    while started.elapsed() < delay {
+        std::thread::sleep(CHANGE_POLL_INTERVAL);
+        if change_count() != written_count {
+            log::info!("Clipboard changed after paste, not restoring previous contents");
+            return;
+        }
+    }
+
+    snapshot.restore();
+}
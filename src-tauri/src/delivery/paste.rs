@@ -0,0 +1,48 @@
+// Only include rdev on desktop platforms
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+use rdev::{simulate, EventType, Key, SimulateError};
+
+/// Sends a single key event, leaving a short gap so the target app registers it.
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+pub(crate) fn send(event_type: EventType) {
+    if let Err(SimulateError) = simulate(&event_type) {
+        log::error!("Failed to send {:?}", event_type);
+    }
+    // Introduce a small delay between events to ensure they are registered
+    std::thread::sleep(std::time::Duration::from_millis(20));
+}
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+pub fn simulate_paste() {
+    #[cfg(target_os = "linux")]
+    if crate::delivery::linux::is_wayland() {
+        crate::delivery::linux::paste();
+        return;
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        // macOS uses the Command key for shortcuts
+        send(EventType::KeyPress(Key::MetaLeft));
+        send(EventType::KeyPress(Key::KeyV));
+        send(EventType::KeyRelease(Key::KeyV));
+        send(EventType::KeyRelease(Key::MetaLeft));
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        // Windows and Linux typically use the Control key for shortcuts. On
+        // Linux rdev goes through XTest, which covers X11 sessions
+        send(EventType::KeyPress(Key::ControlLeft));
+        send(EventType::KeyPress(Key::KeyV));
+        send(EventType::KeyRelease(Key::KeyV));
+        send(EventType::KeyRelease(Key::ControlLeft));
+    }
+}
+
+// Add a no-op version for mobile platforms
+#[cfg(any(target_os = "android", target_os = "ios"))]
+pub fn simulate_paste() {
+    // No-op on mobile platforms
+    log::warn!("Paste simulation is not supported on mobile platforms");
+}
@@ -0,0 +1,165 @@
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+use crate::delivery::paste::send;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+use rdev::{EventType, Key};
+
+/// Types `text` into the focused app one character at a time, for apps that
+/// block pasting. Newlines and tabs go through rdev as Return/Tab presses,
+/// everything else is injected as Unicode where the platform supports it so
+/// the result doesn't depend on the active keyboard layout.
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+pub fn type_text(text: &str, chars_per_second: u32) {
+    #[cfg(target_os = "linux")]
+    if crate::delivery::linux::is_wayland() {
+        crate::delivery::linux::type_text(text, chars_per_second);
+        return;
+    }
+
+    let delay = std::time::Duration::from_secs_f64(1.0 / chars_per_second.max(1) as f64);
+
+    for ch in text.chars() {
+        match ch {
+            '\n' => tap(Key::Return),
+            '\t' => tap(Key::Tab),
+            '\r' => continue,
+            _ => type_char(ch),
+        }
+        std::thread::sleep(delay);
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "ios"))]
+pub fn type_text(_text: &str, _chars_per_second: u32) {
+    log::warn!("Keystroke simulation is not supported on mobile platforms");
+}
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn tap(key: Key) {
+    send(EventType::KeyPress(key));
+    send(EventType::KeyRelease(key));
+}
+
+#[cfg(target_os = "macos")]
+fn type_char(ch: char) {
+    use core_graphics::event::{CGEvent, CGEventTapLocation};
+    use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
+
+    let Ok(source) = CGEventSource::new(CGEventSourceStateID::HIDSystemState) else {
+        log::error!("Failed to create event source");
+        return;
+    };
+
+    let mut buffer = [0u8; 4];
+    let string = ch.encode_utf8(&mut buffer);
+
+    for key_down in [true, false] {
+        match CGEvent::new_keyboard_event(source.clone(), 0, key_down) {
+            Ok(event) => {
+                event.set_string(string);
+                event.post(CGEventTapLocation::HID);
+            }
+            Err(_) => log::error!("Failed to create keyboard event"),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn type_char(ch: char) {
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{
+        SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYEVENTF_KEYUP, KEYEVENTF_UNICODE,
+    };
+
+    let mut units = [0u16; 2];
+    let mut inputs = Vec::with_capacity(4);
+    for &unit in ch.encode_utf16(&mut units).iter() {
+        for flags in [KEYEVENTF_UNICODE, KEYEVENTF_UNICODE | KEYEVENTF_KEYUP] {
+            inputs.push(INPUT {
+                r#type: INPUT_KEYBOARD,
+                Anonymous: INPUT_0 {
+                    ki: KEYBDINPUT {
+                        wVk: 0,
+                        wScan: unit,
+                        dwFlags: flags,
+                        time: 0,
+                        dwExtraInfo: 0,
+                    },
+                },
+            });
+        }
+    }
+
+    let sent = unsafe { SendInput(inputs.len() as u32, inputs.as_ptr(), std::mem::size_of::<INPUT>() as i32) };
+    if sent as usize != inputs.len() {
+        log::error!("SendInput only injected {} of {} events", sent, inputs.len());
+    }
+}
+
+// Without a Unicode injection API (X11 via XTest), fall back to US-layout key
+// presses and skip anything that can't be typed that way
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "android", target_os = "ios")))]
+fn type_char(ch: char) {
+    match key_for_char(ch) {
+        Some((key, true)) => {
+            send(EventType::KeyPress(Key::ShiftLeft));
+            tap(key);
+            send(EventType::KeyRelease(Key::ShiftLeft));
+        }
+        Some((key, false)) => tap(key),
+        None => log::warn!("Cannot type character U+{:04X} on this platform", ch as u32),
+    }
+}
+
+/// US-layout key and whether Shift is needed for `ch`.
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "android", target_os = "ios")))]
+fn key_for_char(ch: char) -> Option<(Key, bool)> {
+    const LETTERS: [Key; 26] = [
+        Key::KeyA, Key::KeyB, Key::KeyC, Key::KeyD, Key::KeyE, Key::KeyF, Key::KeyG, Key::KeyH, Key::KeyI,
+        Key::KeyJ, Key::KeyK, Key::KeyL, Key::KeyM, Key::KeyN, Key::KeyO, Key::KeyP, Key::KeyQ, Key::KeyR,
+        Key::KeyS, Key::KeyT, Key::KeyU, Key::KeyV, Key::KeyW, Key::KeyX, Key::KeyY, Key::KeyZ,
+    ];
+    const DIGITS: [Key; 10] = [
+        Key::Num0, Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5, Key::Num6, Key::Num7, Key::Num8,
+        Key::Num9,
+    ];
+
+    let mapped = match ch {
+        'a'..='z' => (LETTERS[ch as usize - 'a' as usize], false),
+        'A'..='Z' => (LETTERS[ch as usize - 'A' as usize], true),
+        '0'..='9' => (DIGITS[ch as usize - '0' as usize], false),
+        ' ' => (Key::Space, false),
+        '-' => (Key::Minus, false),
+        '_' => (Key::Minus, true),
+        '=' => (Key::Equal, false),
+        '+' => (Key::Equal, true),
+        '[' => (Key::LeftBracket, false),
+        '{' => (Key::LeftBracket, true),
+        ']' => (Key::RightBracket, false),
+        '}' => (Key::RightBracket, true),
+        '\\' => (Key::BackSlash, false),
+        '|' => (Key::BackSlash, true),
+        ';' => (Key::SemiColon, false),
+        ':' => (Key::SemiColon, true),
+        '\'' => (Key::Quote, false),
+        '"' => (Key::Quote, true),
+        ',' => (Key::Comma, false),
+        '<' => (Key::Comma, true),
+        '.' => (Key::Dot, false),
+        '>' => (Key::Dot, true),
+        '/' => (Key::Slash, false),
+        '?' => (Key::Slash, true),
+        '`' => (Key::BackQuote, false),
+        '~' => (Key::BackQuote, true),
+        '!' => (Key::Num1, true),
+        '@' => (Key::Num2, true),
+        '#' => (Key::Num3, true),
+        '$' => (Key::Num4, true),
+        '%' => (Key::Num5, true),
+        '^' => (Key::Num6, true),
+        '&' => (Key::Num7, true),
+        '*' => (Key::Num8, true),
+        '(' => (Key::Num9, true),
+        ')' => (Key::Num0, true),
+        _ => return None,
+    };
+    Some(mapped)
+}
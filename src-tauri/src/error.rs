@@ -0,0 +1,148 @@
+use crate::api::ApiError;
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::sync::PoisonError;
+
+/// Error returned by every command. Serializes to
+/// `{ kind, message, retryable, status?, body? }` so the frontend can pick a
+/// message per kind and only offer a retry when it can help.
+#[derive(Debug, Clone)]
+pub enum AppError {
+    /// No signed-in user, or the backend rejected the token
+    Unauthenticated,
+    /// The backend couldn't be reached
+    Network(String),
+    Timeout,
+    /// The backend answered with a non-2xx status
+    Server { status: u16, body: String },
+    /// The backend answered with something we couldn't parse
+    InvalidResponse(String),
+    /// The request itself was wrong, retrying won't help
+    Validation(String),
+    NotFound(String),
+    /// Recording, input devices or the WAV file
+    Audio(String),
+    Io(String),
+    /// Aborted by logout or a profile switch
+    Cancelled,
+    Internal(String),
+}
+
+impl AppError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Unauthenticated => "unauthenticated",
+            Self::Network(_) => "network",
+            Self::Timeout => "timeout",
+            Self::Server { .. } => "server",
+            Self::InvalidResponse(_) => "invalidResponse",
+            Self::Validation(_) => "validation",
+            Self::NotFound(_) => "notFound",
+            Self::Audio(_) => "audio",
+            Self::Io(_) => "io",
+            Self::Cancelled => "cancelled",
+            Self::Internal(_) => "internal",
+        }
+    }
+
+    /// Whether trying the same call again later might succeed.
+    pub fn retryable(&self) -> bool {
+        match self {
+            Self::Network(_) | Self::Timeout => true,
+            Self::Server { status, .. } => *status >= 500 || *status == 429,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unauthenticated => write!(f, "User not authenticated"),
+            Self::Network(e) => write!(f, "Network error: {}", e),
+            Self::Timeout => write!(f, "Request timed out"),
+            Self::Cancelled => write!(f, "Cancelled"),
+            Self::Server { status, body } => write!(f, "Server error ({}): {}", status, body),
+            Self::InvalidResponse(e) => write!(f, "Unexpected response from server: {}", e),
+            Self::Validation(e) | Self::NotFound(e) | Self::Audio(e) | Self::Io(e) | Self::Internal(e) => {
+                write!(f, "{}", e)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Wire<'a> {
+            kind: &'static str,
+            message: String,
+            retryable: bool,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            status: Option<u16>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            body: Option<&'a str>,
+        }
+
+        let (status, body) = match self {
+            Self::Server { status, body } => (Some(*status), Some(body.as_str())),
+            _ => (None, None),
+        };
+
+        Wire {
+            kind: self.kind(),
+            message: self.to_string(),
+            retryable: self.retryable(),
+            status,
+            body,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl From<ApiError> for AppError {
+    fn from(error: ApiError) -> Self {
+        match error {
+            ApiError::Network(e) => Self::Network(e),
+            ApiError::Timeout => Self::Timeout,
+            ApiError::Server { status: 401 | 403, .. } => Self::Unauthenticated,
+            ApiError::Server { status: 404, message } => Self::NotFound(message),
+            ApiError::Server { status: 400 | 422, message } => Self::Validation(message),
+            ApiError::Server { status, message } => Self::Server { status, body: message },
+            ApiError::Decode(e) => Self::InvalidResponse(e),
+            ApiError::Cancelled => Self::Cancelled,
+        }
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error.to_string())
+    }
+}
+
+impl From<hound::Error> for AppError {
+    fn from(error: hound::Error) -> Self {
+        Self::Audio(error.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(error: rusqlite::Error) -> Self {
+        Self::Io(error.to_string())
+    }
+}
+
+impl From<tauri::Error> for AppError {
+    fn from(error: tauri::Error) -> Self {
+        Self::Internal(error.to_string())
+    }
+}
+
+impl<T> From<PoisonError<T>> for AppError {
+    fn from(error: PoisonError<T>) -> Self {
+        Self::Internal(error.to_string())
+    }
+}
@@ -1,110 +1,78 @@
+use crate::api::{JeffApiClient, TranscribeRequest};
 use crate::audio::{
-    wav_spec_from_config, write_input_data
+    run_capture, run_preroll_capture, wav_spec_from_config, wav_spec_from_stream_config, CaptureEvent,
+    CaptureOutcome, PreRollAttachment, RecorderMessage, SampleWriter,
 };
+#[cfg(target_os = "macos")]
 use crate::audio::macos::volume::{get_default_output_device, get_device_volume};
+use crate::delivery::{route_transcript, TranscriptKind};
+use crate::error::AppError;
+use crate::logging;
 use crate::state::AppState;
 use crate::state::RecordingState;
-use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use crate::AppHandle;
+use cpal::traits::{DeviceTrait, HostTrait};
 use hound::WavWriter;
-use reqwest::multipart::{Form, Part};
-use serde_json::Value;
 use std::sync::mpsc::channel;
-use std::sync::{atomic::Ordering, Arc, Mutex};
+use std::sync::{atomic::Ordering, Arc};
 use std::{thread, time::{Instant, Duration}};
 use tauri::{Emitter, EventTarget};
-use tempfile::Builder;
+use tempfile::{Builder, NamedTempFile};
 use tokio::fs::File;
 use tokio::io::AsyncReadExt;
 
+// Where a recording gets its samples from
+enum CaptureSource {
+    Device(cpal::Device, cpal::StreamConfig),
+    PreRoll(PreRollAttachment),
+}
+
 async fn transcribe_audio(
+    api: JeffApiClient,
     user_id: String,
-    token: String,
     file_path: String,
     refine: bool,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     let start_time = Instant::now();
     log::info!("Starting transcription for file: {}", file_path);
 
     // Read the file into a buffer
-    let mut file = File::open(&file_path)
-        .await
-        .map_err(|e| format!("Failed to open file: {}", e))?;
+    let mut file = File::open(&file_path).await?;
     let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer)
-        .await
-        .map_err(|e| format!("Failed to read file: {}", e))?;
+    file.read_to_end(&mut buffer).await?;
 
-    // Log the file size and first few bytes for debugging
+    // Log the file size for debugging
     log::info!("Audio file size: {} bytes", buffer.len());
-
-    // Create the multipart form
-    let part = Part::bytes(buffer)
-        .file_name("audio.wav")
-        .mime_str("audio/wav")
-        .map_err(|e| format!("Failed to create form part: {}", e))?;
-
-    let form = Form::new()
-        .part("file", part)
-        .text("refine", refine.to_string())
-        .text("userId", user_id.clone());
-
     log::info!("Sending transcription request for user: {}", user_id);
 
-    // Send the request
-    let client = reqwest::Client::new();
-    let response = client
-        // .post("http://localhost:8787/api/transcribe")
-        .post("https://jeff-ai-cf-be.mrboutte21.workers.dev/api/transcribe")
-        .header("Authorization", format!("Bearer {}", token))
-        .multipart(form)
-        .send()
+    let response = api
+        .transcribe(TranscribeRequest {
+            audio: buffer,
+            refine,
+            user_id,
+        })
         .await
         .map_err(|e| {
-            log::error!("Failed to send transcription request: {}", e);
-            e.to_string()
+            log::error!("Failed to transcribe audio: {}", e);
+            AppError::from(e)
         })?;
 
-    // Log the response status
-    log::info!("Response status: {}", response.status());
-
-    let response_text = response.text().await.map_err(|e| {
-        log::error!("Failed to get response text: {}", e);
-        e.to_string()
-    })?;
-
-    log::info!("Raw response: {}", response_text);
-
-    let json_value: Value = serde_json::from_str(&response_text).map_err(|e| {
-        log::error!("Failed to parse JSON: {}", e);
-        e.to_string()
-    })?;
+    log::info!("Transcription response: {}", logging::content(&response));
 
-    // Check for error in response
-    if let Some(error) = json_value.get("error") {
-        let error_msg = error.as_str().unwrap_or("Unknown error");
-        log::error!("Server returned error: {}", error_msg);
-        return Err(error_msg.to_string());
-    }
-
-    // Check for "no dialog" message
-    if let Some(message) = json_value.get("message") {
-        if message.as_str() == Some("No dialog detected") {
-            log::info!("No dialog detected in audio");
-            return Ok("".to_string());
-        }
+    if response.no_dialog() {
+        log::info!("No dialog detected in audio");
+        return Ok("".to_string());
     }
 
     // Extract the transcription
     let transcription = if refine {
-        json_value["refined"]
-            .as_str()
-            .ok_or_else(|| "No refined transcription in response".to_string())?
-            .to_string()
+        response
+            .refined
+            .ok_or_else(|| AppError::InvalidResponse("No refined transcription in response".to_string()))?
     } else {
-        json_value["transcription"]
-            .as_str()
-            .ok_or_else(|| "No transcription in response".to_string())?
-            .to_string()
+        response
+            .transcription
+            .ok_or_else(|| AppError::InvalidResponse("No transcription in response".to_string()))?
     };
 
     let duration = start_time.elapsed();
@@ -115,136 +83,175 @@ async fn transcribe_audio(
 
 
 #[tauri::command]
-pub async fn start_recording(state: tauri::State<'_, AppState>) -> Result<(), String> {
-    let mut recording_state = state.recording_state.lock().map_err(|e| e.to_string())?;
+pub async fn start_recording(state: tauri::State<'_, AppState>) -> Result<(), AppError> {
+    begin_recording(&state)
+}
+
+/// Opens the input and starts writing to a fresh temp WAV file. Shared by the
+/// `start_recording` command and push-to-talk.
+pub fn begin_recording(state: &AppState) -> Result<(), AppError> {
+    let mut recording_state = state.recording_state.lock()?;
 
     match *recording_state {
         RecordingState::Stopped => {
-            *recording_state = RecordingState::Recording;
-
             log::info!("Starting recording");
 
-            // Get and store the default output device
-            let device_id = get_default_output_device().map_err(|e| format!("Failed to get default output device: {}", e))?;
-            *state.audio_device_id.lock().map_err(|e| e.to_string())? = Some(device_id);
+            // Get and store the default output device and its current volume.
+            // Only macOS has volume control wired up so far
+            #[cfg(target_os = "macos")]
+            {
+                let device_id = get_default_output_device()
+                    .map_err(|e| AppError::Audio(format!("Failed to get default output device: {}", e)))?;
+                *state.audio_device_id.lock()? = Some(device_id);
+
+                let current_volume = get_device_volume(device_id)
+                    .map_err(|e| AppError::Audio(format!("Failed to get device volume: {}", e)))?;
+                *state.original_volume.lock()? = Some(current_volume);
+            }
 
-            // Get and store the current volume
-            let current_volume = get_device_volume(device_id).map_err(|e| format!("Failed to get device volume: {}", e))?;
-            *state.original_volume.lock().map_err(|e| e.to_string())? = Some(current_volume);
+            let (sender, receiver) = channel();
 
-             // Introduce a small delay so that our sound effect is heard
-            thread::sleep(Duration::from_millis(300));
+            // Attach to the warm pre-roll stream when it's running, so the
+            // recording includes what was said just before the hotkey
+            let pre_roll_attachment = match state.pre_roll.lock()?.as_ref() {
+                Some(pre_roll) => pre_roll
+                    .attach(sender.clone())
+                    .map_err(|e| log::warn!("Falling back to a fresh input stream: {}", e))
+                    .ok(),
+                None => None,
+            };
+
+            if pre_roll_attachment.is_none() {
+                // Introduce a small delay so that our sound effect is heard
+                thread::sleep(Duration::from_millis(300));
+            }
 
             // Set volume to 0
             // fade_volume(device_id, current_volume, 0.0, 6, 100); // 6 steps, 100ms between steps
 
-            // Create temp file with .wav extension
+            // Create temp file with .wav extension in the profile's recordings
+            // dir, so logging out or switching profiles can clean it up
+            let recordings_dir = state.profile_paths()?.recordings_dir();
+            std::fs::create_dir_all(&recordings_dir)?;
             let temp_file = Builder::new()
                 .prefix("recording_")
                 .suffix(".wav")
-                .tempfile()
-                .map_err(|e| format!("Failed to create temp file: {}", e))?;
+                .tempfile_in(&recordings_dir)?;
 
             let output_path = temp_file.path().to_path_buf();
             log::info!("Recording to temporary file: {:?}", output_path);
 
-            let host = cpal::default_host();
-            let device = host
-                .default_input_device()
-                .ok_or_else(|| "No input device available".to_string())?;
-
-            log::info!("Selected input device: {}", device.name().map_err(|e| e.to_string())?);
-
-            if let Ok(configs) = device.supported_input_configs() {
-                for config in configs {
-                    log::info!(
-                        "  Rate: {:?}-{:?}, Channels: {}, Format: {:?}",
-                        config.min_sample_rate(),
-                        config.max_sample_rate(),
-                        config.channels(),
-                        config.sample_format()
-                    );
+            let (source, spec) = match pre_roll_attachment {
+                Some(attachment) => {
+                    let spec = wav_spec_from_stream_config(&attachment.config);
+                    (CaptureSource::PreRoll(attachment), spec)
                 }
-            }
+                None => {
+                    let host = cpal::default_host();
+                    let device = host
+                        .default_input_device()
+                        .ok_or_else(|| AppError::Audio("No input device available".to_string()))?;
+
+                    let device_name = device.name().map_err(|e| AppError::Audio(e.to_string()))?;
+                    log::info!("Selected input device: {}", device_name);
+
+                    if let Ok(configs) = device.supported_input_configs() {
+                        for config in configs {
+                            log::info!(
+                                "  Rate: {:?}-{:?}, Channels: {}, Format: {:?}",
+                                config.min_sample_rate(),
+                                config.max_sample_rate(),
+                                config.channels(),
+                                config.sample_format()
+                            );
+                        }
+                    }
 
-            let config = device.default_input_config().map_err(|e| e.to_string())?;
+                    let config = device
+                        .default_input_config()
+                        .map_err(|e| AppError::Audio(e.to_string()))?;
+                    let spec = wav_spec_from_config(&config);
+                    (CaptureSource::Device(device, config.into()), spec)
+                }
+            };
 
-            let spec = wav_spec_from_config(&config);
-            let writer = WavWriter::create(&output_path, spec).map_err(|e| e.to_string())?;
+            let writer = WavWriter::create(&output_path, spec)?;
             let path_str = output_path.to_string_lossy().to_string();
 
-            let writer = Arc::new(Mutex::new(Some((writer, path_str.clone()))));
-            let writer_clone = Arc::clone(&writer);
+            let noise_suppression = {
+                let preferences = state.preferences.lock()?;
+                preferences
+                    .audio
+                    .noise_suppression
+                    .then_some(preferences.audio.noise_suppression_strength)
+            };
 
-            let (sender, receiver) = channel();
-            *state.recording_sender.lock().map_err(|e| e.to_string())? = Some(sender);
+            let writer = SampleWriter::new(writer, path_str, noise_suppression);
 
-            *state.audio_writer.lock().map_err(|e| e.to_string())? = Some(Arc::clone(&writer));
+            // Only now that the input and the file are ready, so a failed
+            // start doesn't block every later one
+            *recording_state = RecordingState::Recording;
+            *state.recording_sender.lock()? = Some(sender.clone());
 
             state.is_recording.store(true, Ordering::SeqCst);
             let recording_flag = Arc::clone(&state.is_recording);
 
             // Store temp_file handle in state to prevent premature deletion
-            *state.temp_file.lock().map_err(|e| e.to_string())? = Some(temp_file);
+            *state.temp_file.lock()? = Some(temp_file);
+
+            let app_handle = state.app_handle.clone();
 
             // Start recording
-            std::thread::spawn(move || {
-                let recording_flag_stream = Arc::clone(&recording_flag);
-                let stream = device
-                    .build_input_stream(
-                        &config.into(),
-                        move |data: &[f32], _| {
-                            if recording_flag_stream.load(Ordering::SeqCst) {
-                                let max_amplitude = data.iter().map(|&x| x.abs()).fold(0.0f32, f32::max);
-                                let has_signal = max_amplitude > 0.00001;
-
-                                if has_signal {
-                                    write_input_data(data, &writer_clone);
-                                }
+            let handle = std::thread::spawn(move || {
+                let event_handle = app_handle.clone();
+                let outcome = match source {
+                    CaptureSource::PreRoll(attachment) => run_preroll_capture(attachment, writer, receiver),
+                    CaptureSource::Device(device, config) => run_capture(
+                        device,
+                        config,
+                        writer,
+                        Arc::clone(&recording_flag),
+                        sender,
+                        receiver,
+                        move |event| match event {
+                            CaptureEvent::DeviceChanged(name) => {
+                                let _ = event_handle.emit_to(
+                                    EventTarget::any(),
+                                    "recording-device-changed",
+                                    Some(name),
+                                );
                             }
                         },
-                        move |err| {
-                            log::error!("Error in audio stream: {}", err);
-                        },
-                        None,
-                    )
-                    .unwrap();
-
-                stream.play().unwrap();
-
-                while recording_flag.load(Ordering::SeqCst) {
-                    if receiver.recv().is_err() {
-                        break;
-                    }
-                }
-
-                if let Ok(mut writer_guard) = writer.lock() {
-                    if let Some((writer, _)) = writer_guard.take() {
-                        writer.finalize().unwrap();
-                    }
+                    ),
+                };
+
+                if let CaptureOutcome::DeviceLost(name) = outcome {
+                    // The WAV file is finalized and kept, so a follow-up
+                    // `stop_recording` still transcribes what was captured
+                    recording_flag.store(false, Ordering::SeqCst);
+                    log::warn!("Recording stopped because the input device was lost: {}", name);
+                    let _ = app_handle.emit_to(EventTarget::any(), "recording-device-lost", Some(name));
                 }
             });
+            *state.recording_thread.lock()? = Some(handle);
 
             Ok(())
         }
-        _ => Err("Recording already in progress".to_string()),
+        _ => Err(AppError::Audio("Recording already in progress".to_string())),
     }
 }
 
-#[tauri::command]
-pub async fn stop_recording(
-    state: tauri::State<'_, AppState>,
-    _app_handle: tauri::AppHandle,
-    token: String,
-    refine: bool,
-) -> Result<(), String> {
+/// Stops capture and waits for the WAV file to be finalized, then hands the
+/// temp file over to the caller. It's deleted when the caller drops it, so a
+/// new recording can start while this one is still being transcribed.
+pub async fn finish_capture(state: &AppState) -> Result<Option<NamedTempFile>, AppError> {
     {
-        let mut recording_state = state.recording_state.lock().map_err(|e| e.to_string())?;
+        let mut recording_state = state.recording_state.lock()?;
         match *recording_state {
             RecordingState::Recording | RecordingState::Paused => {
                 *recording_state = RecordingState::Stopped;
             }
-            RecordingState::Stopped => return Err("Recording not started".to_string()),
+            RecordingState::Stopped => return Err(AppError::Audio("Recording not started".to_string())),
         }
     }
 
@@ -253,89 +260,122 @@ pub async fn stop_recording(
 
     // Restore the original volume
     // if let (Some(device_id), Some(original_volume)) = (
-    //     *state.audio_device_id.lock().map_err(|e| e.to_string())?,
-    //     *state.original_volume.lock().map_err(|e| e.to_string())?,
+    //     *state.audio_device_id.lock()?,
+    //     *state.original_volume.lock()?,
     // ) {
     //     fade_volume(device_id, 0.0, original_volume, 6, 100); // 6 steps, 100ms between steps
     // }
 
-    // Signal the recording thread to stop
-    if let Some(sender) = state
-        .recording_sender
-        .lock()
-        .map_err(|e| e.to_string())?
-        .as_ref()
-    {
-        sender.send(()).map_err(|e| e.to_string())?;
+    // Signal the recording thread to stop. It may already be gone if the
+    // input device was lost mid-recording.
+    if let Some(sender) = state.recording_sender.lock()?.take() {
+        if sender.send(RecorderMessage::Stop).is_err() {
+            log::info!("Recording thread already finished");
+        }
     }
 
-    let audio_file_path = {
-        let audio_writer_guard = state.audio_writer.lock().map_err(|e| e.to_string())?;
-        if let Some(writer_arc) = audio_writer_guard.as_ref() {
-            let writer_guard = writer_arc.lock().map_err(|e| e.to_string())?;
-            writer_guard.as_ref().map(|(_, path)| path.clone())
-        } else {
-            None
-        }
-    };
+    // Wait for the recording thread to finalize the WAV file before reading it
+    let recording_thread = state.recording_thread.lock()?.take();
+    if let Some(handle) = recording_thread {
+        tokio::task::spawn_blocking(move || handle.join())
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .map_err(|_| AppError::Audio("Recording thread panicked".to_string()))?;
+    }
 
-    // Handle transcription if we have a file
-    if let Some(file_path) = audio_file_path {
-        log::info!("Transcribing audio file: {}", file_path);
-
-        // Get the user ID before any async operations
-        let user_id: String = {
-            let user_guard = state.existing_user.lock().map_err(|e| e.to_string())?;
-            user_guard
-                .as_ref()
-                .and_then(|u| Some(u.id.clone()))
-                .ok_or_else(|| "User not authenticated".to_string())?
-        };
-
-        let transcription_result = transcribe_audio(user_id, token, file_path.clone(), refine).await;
-
-        // Clean up and verify temp file deletion
-        if let Ok(mut temp_file_guard) = state.temp_file.lock() {
-            let path = temp_file_guard.as_ref().map(|f| f.path().to_owned());
-            temp_file_guard.take(); // This will remove and delete the temp file
-
-            // Verify deletion
-            if let Some(file_path) = path {
-                if file_path.exists() {
-                    log::warn!("Temporary file still exists at: {:?}", file_path);
-                } else {
-                    log::info!("Successfully deleted temporary file at: {:?}", file_path);
-                }
-            }
-        }
+    let temp_file = state.temp_file.lock()?.take();
+
+    Ok(temp_file)
+}
+
+/// Stops the current recording and deletes the audio without transcribing it.
+pub async fn discard_recording(state: &AppState) -> Result<(), AppError> {
+    let temp_file = finish_capture(state).await?;
+
+    log::info!("Discarding recording");
+    drop(temp_file);
+
+    Ok(())
+}
+
+/// Discards the recording in progress, if there is one.
+pub async fn discard_active_recording(state: &AppState) -> Result<(), AppError> {
+    if matches!(*state.recording_state.lock()?, RecordingState::Stopped) {
+        return Ok(());
+    }
+    discard_recording(state).await
+}
 
-        log::info!("Transcription result: {:?}", transcription_result);
-
-        match transcription_result {
-            Ok(transcript) => {
-                // Only emit if transcript is not empty
-                if !transcript.is_empty() {
-                    let event_name = if refine {
-                        "refined-transcription-complete"
-                    } else {
-                        "transcription-complete"
-                    };
-
-                    state
-                        .app_handle
-                        .emit_to(EventTarget::any(), event_name, Some(transcript))
-                        .map_err(|e| e.to_string())?;
+#[tauri::command]
+pub async fn stop_recording(
+    state: tauri::State<'_, AppState>,
+    _app_handle: AppHandle,
+    refine: bool,
+) -> Result<(), AppError> {
+    stop_and_transcribe(&state, refine).await
+}
+
+/// Stops the current recording, transcribes it and emits and delivers the
+/// transcript.
+pub async fn stop_and_transcribe(state: &AppState, refine: bool) -> Result<(), AppError> {
+    match finish_capture(state).await? {
+        Some(temp_file) => transcribe_recording(state, temp_file, refine).await,
+        None => Ok(()),
+    }
+}
+
+/// Transcribes a finished recording, then emits and delivers the transcript.
+/// The temp file is deleted once the upload is done.
+pub async fn transcribe_recording(state: &AppState, temp_file: NamedTempFile, refine: bool) -> Result<(), AppError> {
+    let file_path = temp_file.path().to_string_lossy().to_string();
+    log::info!("Transcribing audio file: {}", file_path);
+
+    let user_id = state.current_user_id()?;
+    let api = state.api().await?;
+    let transcription_result = transcribe_audio(api, user_id, file_path.clone(), refine).await;
+
+    // Clean up and verify temp file deletion
+    let path = temp_file.path().to_owned();
+    drop(temp_file);
+    if path.exists() {
+        log::warn!("Temporary file still exists at: {:?}", path);
+    } else {
+        log::info!("Successfully deleted temporary file at: {:?}", path);
+    }
+
+    match transcription_result.as_ref() {
+        Ok(transcript) => log::info!("Transcription result: {}", logging::content(transcript)),
+        Err(e) => log::info!("Transcription failed: {}", e),
+    }
+
+    match transcription_result {
+        Ok(transcript) => {
+            // Only emit if transcript is not empty
+            if !transcript.is_empty() {
+                let kind = if refine {
+                    TranscriptKind::Refined
                 } else {
-                    log::info!("Skipping event emission for empty transcript");
-                }
-            }
-            Err(e) => {
+                    TranscriptKind::Raw
+                };
+
                 state
                     .app_handle
-                    .emit_to(EventTarget::any(), "transcription-error", Some(e))
-                    .map_err(|e| e.to_string())?;
+                    .emit_to(EventTarget::any(), kind.event_name(), Some(transcript.clone()))?;
+
+                // Deliver in the background, pasting or typing can take a while
+                let app_handle = state.app_handle.clone();
+                tauri::async_runtime::spawn_blocking(move || {
+                    route_transcript(&app_handle, kind, &transcript);
+                });
+            } else {
+                log::info!("Skipping event emission for empty transcript");
             }
         }
+        Err(e) => {
+            state
+                .app_handle
+                .emit_to(EventTarget::any(), "transcription-error", Some(e))?;
+        }
     }
 
     Ok(())
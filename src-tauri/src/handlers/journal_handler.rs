@@ -0,0 +1,33 @@
+use crate::delivery::append_to_journal;
+use crate::error::AppError;
+use crate::state::AppState;
+use tauri::{Emitter, EventTarget};
+
+#[tauri::command]
+pub async fn append_journal_entry(
+    state: tauri::State<'_, AppState>,
+    text: String,
+) -> Result<String, AppError> {
+    let pattern = {
+        let preferences = state.preferences.lock()?;
+        preferences
+            .delivery
+            .journal_path
+            .clone()
+            .ok_or_else(|| AppError::Validation("No journal file configured".to_string()))?
+    };
+
+    // File locking blocks, keep it off the async runtime
+    let app_handle = state.app_handle.clone();
+    let path = tauri::async_runtime::spawn_blocking(move || append_to_journal(&app_handle, &pattern, &text))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .map_err(AppError::Io)?;
+
+    let path = path.to_string_lossy().to_string();
+    state
+        .app_handle
+        .emit_to(EventTarget::any(), "journal-entry-appended", Some(path.clone()))?;
+
+    Ok(path)
+}
@@ -1,129 +1,61 @@
+use crate::api::{ConvertToMarkdownRequest, MarkdownResponse, RefineTextRequest, RefineTextResponse, Refinement};
+use crate::error::AppError;
+use crate::logging;
 use crate::state::AppState;
-use serde_json::{json, Value};
 
 #[tauri::command]
 pub async fn get_all_refinements(
     state: tauri::State<'_, AppState>,
-    token: String,
-) -> Result<Value, String> {
+) -> Result<Vec<Refinement>, AppError> {
     log::info!("Getting all refinements");
 
-    // Get the user ID before any async operations
-    let user_id: String = {
-        let user_guard = state.existing_user.lock().map_err(|e| e.to_string())?;
-        user_guard
-            .as_ref()
-            .and_then(|u| Some(u.id.clone()))
-            .ok_or_else(|| "User not authenticated".to_string())?
-    };
-
-    log::info!("User ID: {}", user_id);
-
-    let client = reqwest::Client::new();
-    let response = client
-        // .get("http://localhost:8787/api/refinements")
-        .get("https://jeff-ai-cf-be.mrboutte21.workers.dev/api/refinements")
-        .header("Authorization", format!("Bearer {}", token))
-        .header("Content-Type", "application/json")
-        .send()
-        .await
-        .map_err(|e| {
-            log::error!("Failed to get all refinements: {}", e);
-            e.to_string()
-        })?;
-
-    let json_value = response.json::<Value>().await.map_err(|e| {
-        log::error!("Failed to parse response as JSON: {}", e);
-        e.to_string()
+    let refinements = state.api().await?.list_refinements().await.map_err(|e| {
+        log::error!("Failed to get all refinements: {}", e);
+        AppError::from(e)
     })?;
 
-    Ok(json_value)
+    Ok(refinements)
 }
 
 #[tauri::command]
 pub async fn refine_text(
     state: tauri::State<'_, AppState>,
-    token: String,
     text: String,
     context: Option<String>,
-) -> Result<Value, String> {
-    log::info!("Refining text: {}", text);
-
-    // Get the user ID before any async operations
-    let user_id: String = {
-        let user_guard = state.existing_user.lock().map_err(|e| e.to_string())?;
-        user_guard
-            .as_ref()
-            .and_then(|u| Some(u.id.clone()))
-            .ok_or_else(|| "User not authenticated".to_string())?
-    };
+) -> Result<RefineTextResponse, AppError> {
+    log::info!("Refining text: {}", logging::content(&text));
 
-    log::info!("User ID: {}", user_id);
+    if text.trim().is_empty() {
+        return Err(AppError::Validation("Nothing to refine".to_string()));
+    }
 
-    let client = reqwest::Client::new();
-    let response = client
-        // .post("http://localhost:8787/api/refinements")
-        .post("https://jeff-ai-cf-be.mrboutte21.workers.dev/api/refinements")
-        .header("Authorization", format!("Bearer {}", token))
-        .header("Content-Type", "application/json")
-        .json(&json!({
-            "originalText": text,
-            "additionalContext": context,
-            "userId": user_id
-        }))
-        .send()
-        .await
-        .map_err(|e| {
-            log::error!("Failed to create refinement: {}", e);
-            e.to_string()
-        })?;
+    let request = RefineTextRequest {
+        original_text: text,
+        additional_context: context,
+        user_id: state.current_user_id()?,
+    };
 
-    let json_value = response.json::<Value>().await.map_err(|e| {
-        log::error!("Failed to parse response as JSON: {}", e);
-        e.to_string()
+    let refined = state.api().await?.refine_text(&request).await.map_err(|e| {
+        log::error!("Failed to create refinement: {}", e);
+        AppError::from(e)
     })?;
 
-    Ok(json_value)
+    Ok(refined)
 }
 
 #[tauri::command]
 pub async fn convert_to_markdown(
     state: tauri::State<'_, AppState>,
-    token: String,
     html: String,
-) -> Result<Value, String> {
-    log::info!("Converting the following into markdown: {}", html);
-
-    // Get the user ID before any async operations
-    let user_id: String = {
-        let user_guard = state.existing_user.lock().map_err(|e| e.to_string())?;
-        user_guard
-            .as_ref()
-            .and_then(|u| Some(u.id.clone()))
-            .ok_or_else(|| "User not authenticated".to_string())?
-    };
-
-    log::info!("User ID: {}", user_id);
+) -> Result<MarkdownResponse, AppError> {
+    log::info!("Converting to markdown: {}", logging::content(&html));
 
-    let client = reqwest::Client::new();
-    let response = client
-        .post("https://jeff-ai-cf-be.mrboutte21.workers.dev/api/refinements/convert-to-markdown")
-        .header("Authorization", format!("Bearer {}", token))
-        .header("Content-Type", "application/json")
-        .json(&json!({
-            "html": html,
-        }))
-        .send()
-        .await
-        .map_err(|e| {
-            log::error!("Failed to convert to markdown: {}", e);
-            e.to_string()
-        })?;
+    let request = ConvertToMarkdownRequest { html };
 
-    let json_value = response.json::<Value>().await.map_err(|e| {
-        log::error!("Failed to parse response as JSON: {}", e);
-        e.to_string()
+    let markdown = state.api().await?.convert_to_markdown(&request).await.map_err(|e| {
+        log::error!("Failed to convert to markdown: {}", e);
+        AppError::from(e)
     })?;
 
-    Ok(json_value)
+    Ok(markdown)
 }
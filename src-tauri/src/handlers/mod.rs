@@ -1,12 +1,18 @@
 mod audio_handler;
+mod journal_handler;
 mod message_handler;
+mod preferences_handler;
+mod profile_handler;
 mod settings_handler;
 mod task_handler;
 mod user_handler;
 mod weather_handler;
 
 pub use audio_handler::*;
+pub use journal_handler::*;
 pub use message_handler::*;
+pub use preferences_handler::*;
+pub use profile_handler::*;
 pub use settings_handler::*;
 pub use task_handler::*;
 pub use user_handler::*;
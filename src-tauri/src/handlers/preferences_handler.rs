@@ -0,0 +1,74 @@
+use crate::audio::PreRoll;
+use crate::models::{BackendConfig, Preferences};
+use crate::error::AppError;
+use crate::logging;
+use crate::state::AppState;
+use std::time::Duration;
+
+#[tauri::command]
+pub async fn get_preferences(state: tauri::State<'_, AppState>) -> Result<Preferences, AppError> {
+    let preferences = state.preferences.lock()?;
+    Ok(preferences.clone())
+}
+
+#[tauri::command]
+pub async fn update_preferences(
+    state: tauri::State<'_, AppState>,
+    preferences: Preferences,
+) -> Result<Preferences, AppError> {
+    log::info!("Updating preferences");
+    preferences.backend.validate().map_err(AppError::Validation)?;
+
+    preferences
+        .save(&state.profile_paths()?.preferences())
+        .map_err(AppError::Io)?;
+
+    let previous = {
+        let mut current = state.preferences.lock()?;
+        std::mem::replace(&mut *current, preferences.clone())
+    };
+
+    apply_preferences(&state, &previous, &preferences)?;
+
+    Ok(preferences)
+}
+
+/// Brings the pre-roll stream, backend and push-to-talk listener in line with
+/// `preferences` after they replaced `previous`.
+pub fn apply_preferences(state: &AppState, previous: &Preferences, preferences: &Preferences) -> Result<(), AppError> {
+    logging::set_log_content(preferences.diagnostics.log_content);
+
+    // Start, restart or stop the warm input stream to match the new settings
+    if previous.audio.pre_roll != preferences.audio.pre_roll
+        || previous.audio.pre_roll_ms != preferences.audio.pre_roll_ms
+    {
+        let mut pre_roll = state.pre_roll.lock()?;
+        pre_roll.take();
+        if preferences.audio.pre_roll {
+            *pre_roll = Some(PreRoll::start(Duration::from_millis(preferences.audio.pre_roll_ms as u64)));
+        }
+    }
+
+    if previous.backend != preferences.backend {
+        let resolved = BackendConfig::resolve(&preferences.backend);
+        if resolved.from_env {
+            log::warn!("Backend is set by the environment, ignoring the saved preference");
+        } else {
+            log::info!("Switching backend to {}", resolved.base_url);
+            *state.backend.lock()? = resolved;
+        }
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+    if preferences.push_to_talk.enabled {
+        crate::input::ensure_push_to_talk_listener(&state.app_handle);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_backend_config(state: tauri::State<'_, AppState>) -> Result<BackendConfig, AppError> {
+    let backend = state.backend.lock()?;
+    Ok(backend.clone())
+}
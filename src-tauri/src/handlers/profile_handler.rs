@@ -0,0 +1,129 @@
+use crate::auth::{SecretStore, SESSION_SECRET};
+use crate::error::AppError;
+use crate::handlers::{apply_preferences, discard_active_recording};
+use crate::models::Preferences;
+use crate::profile::{Profile, ProfilePaths, ProfileRegistry};
+use crate::state::{AppState, StoredUser, USER_SECRET};
+use crate::tasks::{emit_tasks_changed, TaskStore};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{Emitter, EventTarget, Manager};
+use ts_rs::TS;
+
+/// Payload of the `profile-changed` event.
+#[derive(Debug, Serialize, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ProfileChanged {
+    pub profile: Profile,
+    pub signed_in: bool,
+}
+
+fn registry_path(state: &AppState) -> Result<PathBuf, AppError> {
+    Ok(state.app_handle.path().app_config_dir()?.join("profiles.json"))
+}
+
+fn paths_for(state: &AppState, id: &str) -> Result<ProfilePaths, AppError> {
+    let path = state.app_handle.path();
+    Ok(ProfilePaths::new(&path.app_config_dir()?, &path.app_data_dir()?, id))
+}
+
+#[tauri::command]
+pub async fn list_profiles(state: tauri::State<'_, AppState>) -> Result<ProfileRegistry, AppError> {
+    Ok(ProfileRegistry::load(&registry_path(&state)?))
+}
+
+#[tauri::command]
+pub async fn create_profile(state: tauri::State<'_, AppState>, name: String) -> Result<Profile, AppError> {
+    let path = registry_path(&state)?;
+    let mut registry = ProfileRegistry::load(&path);
+    let profile = registry.add(&name).map_err(AppError::Validation)?;
+    registry.save(&path).map_err(AppError::Io)?;
+
+    log::info!("Created profile {}", profile.id);
+    Ok(profile)
+}
+
+/// Makes `id` the active profile. Everything belonging to the old one is torn
+/// down first: in-flight requests are cancelled and any recording in progress
+/// is discarded. Then the new profile's preferences, session, user and tasks
+/// are loaded, and only once they are is it saved as the active profile.
+/// `profile-changed` tells the frontend whether it's signed in and
+/// `tasks-changed` hands it the new task list.
+#[tauri::command]
+pub async fn switch_profile(state: tauri::State<'_, AppState>, id: String) -> Result<Profile, AppError> {
+    let path = registry_path(&state)?;
+    let mut registry = ProfileRegistry::load(&path);
+    let profile = registry
+        .get(&id)
+        .cloned()
+        .ok_or_else(|| AppError::NotFound(format!("No profile with ID {}", id)))?;
+
+    log::info!("Switching to profile {}", profile.id);
+    state.cancel_work()?;
+    if let Err(e) = discard_active_recording(&state).await {
+        log::warn!("Failed to discard recording on profile switch: {}", e);
+    }
+
+    // Open everything the new profile needs before touching the current
+    // state, so a failure leaves the old profile in place
+    let paths = paths_for(&state, &profile.id)?;
+    let preferences = Preferences::load(&paths.preferences());
+    let secrets = Arc::new(SecretStore::for_profile(&paths));
+    let task_store = Arc::new(TaskStore::open(&paths.task_db())?);
+    *state.profile.lock()? = paths;
+    *state.tasks.lock()? = Arc::clone(&task_store);
+
+    let previous = {
+        let mut current = state.preferences.lock()?;
+        std::mem::replace(&mut *current, preferences.clone())
+    };
+    apply_preferences(&state, &previous, &preferences)?;
+
+    *state.secrets.lock()? = Arc::clone(&secrets);
+    state.session.switch(Arc::clone(&secrets)).await;
+    let stored_user = StoredUser::load(&secrets);
+    *state.user.lock()? = stored_user.user;
+    *state.existing_user.lock()? = stored_user.existing_user;
+
+    // Last, so the next launch only starts on a profile that opened. If this
+    // fails the switch still holds until then.
+    registry.active = profile.id.clone();
+    if let Err(e) = registry.save(&path) {
+        log::error!("Failed to save the active profile: {}", e);
+    }
+
+    let signed_in = state.session.get().await.is_some();
+    state.app_handle.emit_to(
+        EventTarget::any(),
+        "profile-changed",
+        ProfileChanged {
+            profile: profile.clone(),
+            signed_in,
+        },
+    )?;
+    emit_tasks_changed(&state, &task_store)?;
+    state.request_sync();
+
+    Ok(profile)
+}
+
+/// Deletes an inactive profile along with its settings, caches and stored
+/// credentials.
+#[tauri::command]
+pub async fn delete_profile(state: tauri::State<'_, AppState>, id: String) -> Result<(), AppError> {
+    let path = registry_path(&state)?;
+    let mut registry = ProfileRegistry::load(&path);
+    let profile = registry.remove(&id).map_err(AppError::Validation)?;
+
+    let paths = paths_for(&state, &profile.id)?;
+    let secrets = SecretStore::for_profile(&paths);
+    secrets.delete(SESSION_SECRET);
+    secrets.delete(USER_SECRET);
+    paths.remove_all()?;
+
+    registry.save(&path).map_err(AppError::Io)?;
+    log::info!("Deleted profile {}", profile.id);
+    Ok(())
+}
@@ -1,8 +1,10 @@
-use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+use crate::api::{Units, UserSettings, UserSettingsRequest};
+use crate::error::AppError;
+use crate::logging;
 use crate::state::AppState;
+use serde::{Deserialize, Serialize};
 
-// Separate structs for incoming and outgoing data
+// Settings as entered in the UI, the user ID is added from state
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UserSettingsInput {
     lat: Option<f64>,
@@ -10,169 +12,78 @@ pub struct UserSettingsInput {
     city: Option<String>,
     state: Option<String>,
     country: Option<String>,
-    units: String,
-    language: String,
-}
-
-#[derive(Debug, Serialize)]
-struct UserSettingsRequest {
-    userId: String,
-    lat: Option<f64>,
-    lon: Option<f64>,
-    city: Option<String>,
-    state: Option<String>,
-    country: Option<String>,
-    units: String,
+    units: Units,
     language: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct UserSettings {
-    #[serde(rename = "userId")]
-    user_id: String,
-    lat: Option<f64>,
-    lon: Option<f64>,
-    city: Option<String>,
-    state: Option<String>,
-    country: Option<String>,
-    units: String,
-    language: String,
+impl UserSettingsInput {
+    fn validate(&self) -> Result<(), AppError> {
+        if self.lat.is_some() != self.lon.is_some() {
+            return Err(AppError::Validation("Latitude and longitude must be set together".to_string()));
+        }
+        Ok(())
+    }
+
+    fn into_request(self, user_id: String) -> UserSettingsRequest {
+        UserSettingsRequest {
+            user_id,
+            lat: self.lat,
+            lon: self.lon,
+            city: self.city,
+            state: self.state,
+            country: self.country,
+            units: self.units,
+            language: self.language,
+        }
+    }
 }
 
 #[tauri::command]
 pub async fn get_user_settings(
     state: tauri::State<'_, AppState>,
-    token: String,
-) -> Result<Value, String> {
+) -> Result<Option<UserSettings>, AppError> {
     log::info!("Fetching user settings");
 
-    // Get the user ID before any async operations
-    let user_id: String = {
-        let user_guard = state.existing_user.lock().map_err(|e| e.to_string())?;
-        user_guard
-            .as_ref()
-            .and_then(|u| Some(u.id.clone()))
-            .ok_or_else(|| "User not authenticated".to_string())?
-    };
-
-    log::info!("Getting settings for user: {}", user_id);
-
-    let client = reqwest::Client::new();
-    let response = client
-        .get("https://jeff-ai-cf-be.mrboutte21.workers.dev/api/settings")
-        .header("Authorization", format!("Bearer {}", token))
-        .header("Content-Type", "application/json")
-        .send()
-        .await
-        .map_err(|e| {
-            log::error!("Failed to fetch user settings: {}", e);
-            e.to_string()
-        })?;
-
-    let json_value = response.json::<Value>().await.map_err(|e| {
-        log::error!("Failed to parse response as JSON: {}", e);
-        e.to_string()
+    let settings = state.api().await?.get_settings().await.map_err(|e| {
+        log::error!("Failed to fetch user settings: {}", e);
+        AppError::from(e)
     })?;
 
-    Ok(json_value)
+    Ok(settings)
 }
 
 #[tauri::command]
 pub async fn create_user_settings(
     state: tauri::State<'_, AppState>,
-    token: String,
     settings: UserSettingsInput,
-) -> Result<Value, String> {
-    log::info!("Creating user settings: {:?}", settings);
-
-    // Get the user ID before any async operations
-    let user_id: String = {
-        let user_guard = state.existing_user.lock().map_err(|e| e.to_string())?;
-        user_guard
-            .as_ref()
-            .and_then(|u| Some(u.id.clone()))
-            .ok_or_else(|| "User not authenticated".to_string())?
-    };
+) -> Result<UserSettings, AppError> {
+    log::info!("Creating user settings: {}", logging::content(&settings));
 
-    // Create the request payload with user_id
-    let settings_request = UserSettingsRequest {
-        userId: user_id,
-        lat: settings.lat,
-        lon: settings.lon,
-        city: settings.city,
-        state: settings.state,
-        country: settings.country,
-        units: settings.units,
-        language: settings.language,
-    };
+    settings.validate()?;
+    let request = settings.into_request(state.current_user_id()?);
 
-    let client = reqwest::Client::new();
-    let response = client
-        .post("https://jeff-ai-cf-be.mrboutte21.workers.dev/api/settings")
-        .header("Authorization", format!("Bearer {}", token))
-        .header("Content-Type", "application/json")
-        .json(&settings_request)
-        .send()
-        .await
-        .map_err(|e| {
-            log::error!("Failed to create user settings: {}", e);
-            e.to_string()
-        })?;
-
-    let json_value = response.json::<Value>().await.map_err(|e| {
-        log::error!("Failed to parse response as JSON: {}", e);
-        e.to_string()
+    let settings = state.api().await?.create_settings(&request).await.map_err(|e| {
+        log::error!("Failed to create user settings: {}", e);
+        AppError::from(e)
     })?;
 
-    Ok(json_value)
+    Ok(settings)
 }
 
 #[tauri::command]
 pub async fn update_user_settings(
     state: tauri::State<'_, AppState>,
-    token: String,
     settings: UserSettingsInput,
-) -> Result<Value, String> {
-    log::info!("Updating user settings: {:?}", settings);
-
-    // Get the user ID before any async operations
-    let user_id: String = {
-        let user_guard = state.existing_user.lock().map_err(|e| e.to_string())?;
-        user_guard
-            .as_ref()
-            .and_then(|u| Some(u.id.clone()))
-            .ok_or_else(|| "User not authenticated".to_string())?
-    };
-
-    // Create the request payload with user_id
-    let settings_request = UserSettingsRequest {
-        userId: user_id,
-        lat: settings.lat,
-        lon: settings.lon,
-        city: settings.city,
-        state: settings.state,
-        country: settings.country,
-        units: settings.units,
-        language: settings.language,
-    };
+) -> Result<UserSettings, AppError> {
+    log::info!("Updating user settings: {}", logging::content(&settings));
 
-    let client = reqwest::Client::new();
-    let response = client
-        .patch("https://jeff-ai-cf-be.mrboutte21.workers.dev/api/settings")
-        .header("Authorization", format!("Bearer {}", token))
-        .header("Content-Type", "application/json")
-        .json(&settings_request)
-        .send()
-        .await
-        .map_err(|e| {
-            log::error!("Failed to update user settings: {}", e);
-            e.to_string()
-        })?;
+    settings.validate()?;
+    let request = settings.into_request(state.current_user_id()?);
 
-    let json_value = response.json::<Value>().await.map_err(|e| {
-        log::error!("Failed to parse response as JSON: {}", e);
-        e.to_string()
+    let settings = state.api().await?.update_settings(&request).await.map_err(|e| {
+        log::error!("Failed to update user settings: {}", e);
+        AppError::from(e)
     })?;
 
-    Ok(json_value)
+    Ok(settings)
 }
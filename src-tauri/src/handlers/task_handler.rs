@@ -1,195 +1,252 @@
+use crate::api::RefineTextRequest;
+use crate::error::AppError;
+use crate::logging;
+use crate::models::{NewTask, Task, TaskChanges, TaskDraft, TaskId, TaskOccurrence, TaskParser, TaskPreview};
 use crate::state::AppState;
-use serde_json::{json, Value};
-use serde::{Deserialize, Serialize};
-use reqwest::StatusCode;
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Task {
-    id: String,
-    content: String,
-    day: String,
-    completed: bool,
-    #[serde(rename = "userId")]
-    user_id: String,
-    #[serde(rename = "createdAt")]
-    created_at: Option<String>,
-    #[serde(rename = "updatedAt")]
-    updated_at: Option<String>,
-}
+use crate::tasks;
+use chrono::{Duration, Local, NaiveDate, Utc};
+
+// Every task command reads and writes the local store and returns straight
+// away, the sync loop takes changes to the backend when it can reach it
 
 #[tauri::command]
 pub async fn fetch_tasks(
     state: tauri::State<'_, AppState>,
-    token: String,
-) -> Result<Value, String> {
+) -> Result<Vec<Task>, AppError> {
     log::info!("Fetching all tasks");
 
-    // Get the user ID before any async operations
-    let user_id: String = {
-        let user_guard = state.existing_user.lock().map_err(|e| e.to_string())?;
-        user_guard
-            .as_ref()
-            .and_then(|u| Some(u.id.clone()))
-            .ok_or_else(|| "User not authenticated".to_string())?
-    };
-
-    log::info!("User ID: {}", user_id);
-
-    let client = reqwest::Client::new();
-    let response = client
-        // .get("http://localhost:8787/api/tasks")
-        .get("https://jeff-ai-cf-be.mrboutte21.workers.dev/api/tasks")
-        .header("Authorization", format!("Bearer {}", token))
-        .header("Content-Type", "application/json")
-        .send()
-        .await
-        .map_err(|e| {
-            log::error!("Failed to fetch tasks: {}", e);
-            e.to_string()
-        })?;
-
-    let json_value = response.json::<Value>().await.map_err(|e| {
-        log::error!("Failed to parse response as JSON: {}", e);
-        e.to_string()
+    let tasks = state.task_store()?.list().map_err(|e| {
+        log::error!("Failed to fetch tasks: {}", e);
+        e
     })?;
 
-    Ok(json_value)
+    // Remote changes arrive as `tasks-changed`
+    state.request_sync();
+    Ok(tasks)
 }
 
 #[tauri::command]
 pub async fn create_task(
     state: tauri::State<'_, AppState>,
-    token: String,
-    content: String,
-    assigned_date: String,
-) -> Result<Value, String> {
-    log::info!("Creating new task with content: {}", content);
-
-    // Get the user ID before any async operations
-    let user_id: String = {
-        let user_guard = state.existing_user.lock().map_err(|e| e.to_string())?;
-        user_guard
-            .as_ref()
-            .and_then(|u| Some(u.id.clone()))
-            .ok_or_else(|| "User not authenticated".to_string())?
-    };
+    task: NewTask,
+) -> Result<Task, AppError> {
+    log::info!("Creating new task with content: {}", logging::content(&task.content));
 
-    // Create the task payload matching the API's expected format
-    let task_payload = json!({
-        "task": content,
-        "done": false,
-        "userId": user_id,
-        "assignedDate": assigned_date
-    });
-
-    let client = reqwest::Client::new();
-    let response = client
-        // .post("http://localhost:8787/api/tasks")
-        .post("https://jeff-ai-cf-be.mrboutte21.workers.dev/api/tasks")
-        .header("Authorization", format!("Bearer {}", token))
-        .header("Content-Type", "application/json")
-        .json(&task_payload)
-        .send()
-        .await
-        .map_err(|e| {
-            log::error!("Failed to create task: {}", e);
-            format!("Failed to create task: {}", e)
-        })?;
+    if task.content.trim().is_empty() {
+        return Err(AppError::Validation("Task content can't be empty".to_string()));
+    }
 
-    // Check if the response is successful
-    if !response.status().is_success() {
-        let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-        log::error!("Server returned error: {}", error_text);
-        return Err(format!("Server error: {}", error_text));
+    if let Some(rule) = &task.recurrence {
+        rule.validate().map_err(AppError::Validation)?;
     }
 
-    let json_value = response.json::<Value>().await.map_err(|e| {
-        log::error!("Failed to parse response as JSON: {}", e);
-        format!("Failed to parse response: {}", e)
+    let task = state.task_store()?.create(task).map_err(|e| {
+        log::error!("Failed to create task: {}", e);
+        e
     })?;
 
-    Ok(json_value)
+    state.request_sync();
+    state.reschedule_reminders();
+    Ok(task)
 }
 
 #[tauri::command]
 pub async fn update_task(
-    _state: tauri::State<'_, AppState>,
-    token: String,
-    task_id: u32,
-    content: Option<String>,
-    completed: Option<bool>,
-    day: Option<String>,
-) -> Result<Value, String> {
+    state: tauri::State<'_, AppState>,
+    task_id: TaskId,
+    changes: TaskChanges,
+) -> Result<Task, AppError> {
     log::info!("Updating task: {}", task_id);
 
-    let mut update_data = json!({});
+    if changes.content.as_deref().is_some_and(|content| content.trim().is_empty()) {
+        return Err(AppError::Validation("Task content can't be empty".to_string()));
+    }
 
-    if let Some(content) = content {
-        update_data.as_object_mut().unwrap().insert("task".to_string(), json!(content));
+    if let Some(Some(rule)) = &changes.recurrence {
+        rule.validate().map_err(AppError::Validation)?;
     }
-    if let Some(completed) = completed {
-        update_data.as_object_mut().unwrap().insert("done".to_string(), json!(completed));
+
+    let task = state.task_store()?.update(&task_id, changes).map_err(|e| {
+        log::error!("Failed to update task: {}", e);
+        e
+    })?;
+
+    state.request_sync();
+    state.reschedule_reminders();
+    Ok(task)
+}
+
+#[tauri::command]
+pub async fn delete_task(
+    state: tauri::State<'_, AppState>,
+    task_id: TaskId,
+) -> Result<(), AppError> {
+    log::info!("Deleting task: {}", task_id);
+
+    state.task_store()?.delete(&task_id).map_err(|e| {
+        log::error!("Failed to delete task: {}", e);
+        e
+    })?;
+
+    state.request_sync();
+    state.reschedule_reminders();
+    Ok(())
+}
+
+// A year is plenty for any view and keeps a careless range from walking
+// decades of days
+const MAX_OCCURRENCE_DAYS: i64 = 366;
+
+/// Every task falling between `from` and `to` inclusive, with repeating tasks
+/// expanded into one entry per day they repeat on.
+#[tauri::command]
+pub async fn list_task_occurrences(
+    state: tauri::State<'_, AppState>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<TaskOccurrence>, AppError> {
+    log::info!("Listing task occurrences from {} to {}", from, to);
+
+    if to < from {
+        return Err(AppError::Validation("The range ends before it starts".to_string()));
     }
-    if let Some(day) = day {
-        update_data.as_object_mut().unwrap().insert("day".to_string(), json!(day));
+    if (to - from).num_days() > MAX_OCCURRENCE_DAYS {
+        return Err(AppError::Validation(format!(
+            "Ranges are limited to {} days",
+            MAX_OCCURRENCE_DAYS
+        )));
     }
 
-    let client = reqwest::Client::new();
-    let response = client
-        .patch(&format!(
-            "https://jeff-ai-cf-be.mrboutte21.workers.dev/api/tasks/{}",
-            task_id
-        ))
-        .header("Authorization", format!("Bearer {}", token))
-        .header("Content-Type", "application/json")
-        .json(&update_data)
-        .send()
-        .await
-        .map_err(|e| {
-            log::error!("Failed to update task: {}", e);
-            e.to_string()
+    let store = state.task_store()?;
+    let tasks = store.list()?;
+    let completions = store.completions(from, to)?;
+
+    // Remote changes arrive as `tasks-changed`
+    state.request_sync();
+    Ok(tasks::expand_occurrences(
+        tasks,
+        &completions,
+        from,
+        to,
+        Local::now().date_naive(),
+    ))
+}
+
+/// Completes one occurrence of a task. For a repeating task only that day is
+/// marked, the others are untouched; any other task is completed outright.
+#[tauri::command]
+pub async fn set_occurrence_completed(
+    state: tauri::State<'_, AppState>,
+    task_id: TaskId,
+    date: NaiveDate,
+    completed: bool,
+) -> Result<(), AppError> {
+    log::info!("Setting task {} on {} completed: {}", task_id, date, completed);
+
+    let store = state.task_store()?;
+    let task = store.get(&task_id)?;
+
+    if task.recurrence.is_some() {
+        store.set_completion(&task_id, date, completed).map_err(|e| {
+            log::error!("Failed to complete occurrence: {}", e);
+            e
         })?;
+        state.reschedule_reminders();
+        return Ok(());
+    }
 
-    let json_value = response.json::<Value>().await.map_err(|e| {
-        log::error!("Failed to parse response as JSON: {}", e);
-        e.to_string()
+    let changes = TaskChanges {
+        completed: Some(completed),
+        ..TaskChanges::default()
+    };
+    store.update(&task_id, changes).map_err(|e| {
+        log::error!("Failed to update task: {}", e);
+        e
     })?;
 
-    Ok(json_value)
+    state.request_sync();
+    state.reschedule_reminders();
+    Ok(())
 }
 
+// A day covers any snooze the reminder offers
+const MAX_SNOOZE_MINUTES: u32 = 24 * 60;
+
+/// Shows the reminder for one occurrence of a task again in `minutes`.
+/// Completing it goes through `set_occurrence_completed` like any other.
 #[tauri::command]
-pub async fn delete_task(
-    _state: tauri::State<'_, AppState>,
-    token: String,
-    task_id: u32,
-) -> Result<Value, String> {
-    log::info!("Deleting task: {}", task_id);
+pub async fn snooze_task_reminder(
+    state: tauri::State<'_, AppState>,
+    task_id: TaskId,
+    date: NaiveDate,
+    minutes: u32,
+) -> Result<(), AppError> {
+    log::info!("Snoozing reminder for task {} on {} by {} minutes", task_id, date, minutes);
+
+    if !(1..=MAX_SNOOZE_MINUTES).contains(&minutes) {
+        return Err(AppError::Validation(format!(
+            "Snooze for between 1 and {} minutes",
+            MAX_SNOOZE_MINUTES
+        )));
+    }
 
-    let client = reqwest::Client::new();
-    let response = client
-        .delete(&format!(
-            "https://jeff-ai-cf-be.mrboutte21.workers.dev/api/tasks/{}",
-            task_id
-        ))
-        .header("Authorization", format!("Bearer {}", token))
-        .header("Content-Type", "application/json")
-        .send()
-        .await
+    let store = state.task_store()?;
+    store.get(&task_id)?;
+    store
+        .snooze(&task_id, date, Utc::now() + Duration::minutes(minutes.into()))
         .map_err(|e| {
-            log::error!("Failed to delete task: {}", e);
-            e.to_string()
+            log::error!("Failed to snooze reminder: {}", e);
+            e
         })?;
 
-    match response.status() {
-        StatusCode::NO_CONTENT => Ok(json!({ "success": true })),
-        StatusCode::NOT_FOUND => Err("Task not found".to_string()),
-        StatusCode::UNPROCESSABLE_ENTITY => Err("Invalid task ID".to_string()),
-        _ => {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            log::error!("Unexpected status code: {} - {}", status, error_text);
-            Err(format!("Unexpected error: {}", status))
+    state.reschedule_reminders();
+    Ok(())
+}
+
+/// Splits a transcript into task drafts for the user to review. Nothing is
+/// saved, the frontend calls `create_task` for each draft the user keeps.
+/// With `use_llm` the backend's model does the splitting through the
+/// refinement endpoint, falling back to the local rules if that fails.
+#[tauri::command]
+pub async fn parse_tasks(
+    state: tauri::State<'_, AppState>,
+    transcript: String,
+    use_llm: bool,
+) -> Result<TaskPreview, AppError> {
+    log::info!("Parsing tasks from: {}", logging::content(&transcript));
+
+    if transcript.trim().is_empty() {
+        return Err(AppError::Validation("Nothing to turn into tasks".to_string()));
+    }
+
+    let today = Local::now().date_naive();
+    if use_llm {
+        match llm_drafts(&state, &transcript, today).await {
+            Ok(drafts) => {
+                return Ok(TaskPreview {
+                    drafts,
+                    parser: TaskParser::Llm,
+                })
+            }
+            Err(AppError::Cancelled) => return Err(AppError::Cancelled),
+            Err(e) => log::warn!("Falling back to local task parsing: {}", e),
         }
     }
+
+    Ok(TaskPreview {
+        drafts: tasks::parse_transcript(&transcript, today),
+        parser: TaskParser::Local,
+    })
+}
+
+// The refinement is saved to the user's history like any other
+async fn llm_drafts(state: &AppState, transcript: &str, today: NaiveDate) -> Result<Vec<TaskDraft>, AppError> {
+    let request = RefineTextRequest {
+        original_text: transcript.to_string(),
+        additional_context: Some(tasks::llm_instructions(today)),
+        user_id: state.current_user_id()?,
+    };
+
+    let response = state.api().await?.refine_text(&request).await?;
+    tasks::parse_llm_reply(&response.refined_text).map_err(AppError::InvalidResponse)
 }
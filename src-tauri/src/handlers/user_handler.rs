@@ -1,79 +1,81 @@
+use crate::api::CaptureUserRequest;
+use crate::auth::Session;
+use crate::error::AppError;
+use crate::handlers::discard_active_recording;
 use crate::models::ExistingUser;
 use crate::models::User;
 use crate::state::AppState;
-use reqwest;
-use serde_json::{json, Value};
 
 #[tauri::command]
-pub async fn set_user(state: tauri::State<'_, AppState>, user_data: User) -> Result<(), String> {
-    let mut user = state.user.lock().map_err(|e| e.to_string())?;
-    *user = Some(user_data);
+pub async fn set_user(state: tauri::State<'_, AppState>, user_data: User) -> Result<(), AppError> {
+    *state.user.lock()? = Some(user_data);
+    state.persist_user()
+}
+
+/// Hands the Kinde tokens to the Rust side after sign-in, or after the Kinde
+/// SDK refreshed them itself. From then on every backend call is
+/// authenticated, and the session refreshed, from here.
+#[tauri::command]
+pub async fn set_session(
+    state: tauri::State<'_, AppState>,
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<i64>,
+) -> Result<(), AppError> {
+    let refresh_token = match refresh_token {
+        Some(token) => Some(token),
+        // Keep the refresh token we already have when only the access token changed
+        None => state.session.get().await.and_then(|s| s.refresh_token),
+    };
+
+    state
+        .session
+        .set(Session::new(access_token, refresh_token, expires_in))
+        .await;
     Ok(())
 }
 
 #[tauri::command]
 pub async fn capture_user(
-    token: String,
     auth_user: User,
     state: tauri::State<'_, AppState>,
-) -> Result<ExistingUser, String> {
+) -> Result<ExistingUser, AppError> {
     set_user(state.clone(), auth_user.clone()).await?;
 
-    let client = reqwest::Client::new();
-    let user_data = {
-        let current_user = state.user.lock().map_err(|e| e.to_string())?;
-        current_user
-            .as_ref()
-            .map(|u| {
-                (
-                    u.id.clone(),
-                    u.email.clone(),
-                    format!("{} {}", u.given_name, u.family_name),
-                )
-            })
-            .ok_or_else(|| "No user found in state".to_string())?
+    let request = CaptureUserRequest {
+        auth_user_id: auth_user.id.clone(),
+        email: auth_user.email.clone(),
+        display_name: format!("{} {}", auth_user.given_name, auth_user.family_name),
     };
 
-    log::info!("capture_user user_data: {:?}", user_data);
-
-    let response = client
-        // .post("http://localhost:8787/api/capture")
-        .post("https://jeff-ai-cf-be.mrboutte21.workers.dev/api/capture")
-        .header("Authorization", format!("Bearer {}", token))
-        .header("Content-Type", "application/json")
-        .json(&json!({
-            "authUserId": user_data.0,
-            "email": user_data.1,
-            "displayName": user_data.2
-        }))
-        .send()
-        .await
-        .map_err(|e| {
-            log::error!("Failed to create capture: {}", e);
-            e.to_string()
-        })?;
+    log::info!("Capturing user {}", request.auth_user_id);
 
-    let response_text = response.text().await.map_err(|e| {
-        log::error!("Failed to get response text: {}", e);
-        e.to_string()
+    let response = state.api().await?.capture_user(&request).await.map_err(|e| {
+        log::error!("Failed to create capture: {}", e);
+        AppError::from(e)
     })?;
 
-    log::info!("Raw response text: {}", response_text);
+    log::info!("Captured user {}", response.user.id);
+    *state.existing_user.lock()? = Some(response.user.clone());
+    state.persist_user()?;
+    // Tasks created while signed out can be pushed now that there's a user
+    state.request_sync();
 
-    let json_value: Value = serde_json::from_str(&response_text).map_err(|e| {
-        log::error!("Failed to parse response as JSON: {}", e);
-        e.to_string()
-    })?;
-
-    let existing_user_response: ExistingUser = serde_json::from_value(json_value["user"].clone())
-        .map_err(|e| {
-        log::error!("Failed to parse user data as ExistingUser: {}", e);
-        e.to_string()
-    })?;
-
-    log::info!("capture_user response: {:?}", existing_user_response);
-    let mut existing_user = state.existing_user.lock().map_err(|e| e.to_string())?;
-    *existing_user = Some(existing_user_response.clone());
+    Ok(response.user)
+}
 
-    Ok(existing_user_response)
+/// Signs the user out of the Rust side: aborts in-flight requests, drops any
+/// recording in progress, removes the stored session and wipes the profile's
+/// caches, queued recordings and local tasks.
+#[tauri::command]
+pub async fn logout(state: tauri::State<'_, AppState>) -> Result<(), AppError> {
+    log::info!("Logging out");
+    state.cancel_work()?;
+    if let Err(e) = discard_active_recording(&state).await {
+        log::warn!("Failed to discard recording on logout: {}", e);
+    }
+    state.clear_user().await?;
+    state.task_store()?.clear()?;
+    state.profile_paths()?.wipe_caches()?;
+    Ok(())
 }
@@ -1,5 +1,8 @@
+use crate::api::{GeocodeResult, WeatherResponse};
+use crate::error::AppError;
+use crate::logging;
+use crate::state::AppState;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WeatherLocation {
@@ -10,66 +13,33 @@ pub struct WeatherLocation {
 
 #[tauri::command]
 pub async fn get_weather_location(
-    token: String,
+    state: tauri::State<'_, AppState>,
     location: WeatherLocation,
-) -> Result<Value, String> {
-    log::info!("Fetching weather location for: {:?}", location);
+) -> Result<Vec<GeocodeResult>, AppError> {
+    log::info!("Fetching weather location for: {}", logging::content(&location));
 
     let query = format!("{},{},{}", location.city, location.state, location.country);
-    let url = format!(
-        "https://jeff-ai-cf-be.mrboutte21.workers.dev/api/weather/geocode?q={}",
-        query
-    );
 
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&url)
-        .header("Authorization", format!("Bearer {}", token))
-        .header("Content-Type", "application/json")
-        .send()
-        .await
-        .map_err(|e| {
-            log::error!("Failed to fetch weather location: {}", e);
-            e.to_string()
-        })?;
-
-    let json_value = response.json::<Value>().await.map_err(|e| {
-        log::error!("Failed to parse response as JSON: {}", e);
-        e.to_string()
+    let locations = state.api().await?.geocode(&query).await.map_err(|e| {
+        log::error!("Failed to fetch weather location: {}", e);
+        AppError::from(e)
     })?;
 
-    Ok(json_value)
+    Ok(locations)
 }
 
 #[tauri::command]
 pub async fn get_weather_forecast(
-    token: String,
+    state: tauri::State<'_, AppState>,
     lat: f64,
     lon: f64,
-) -> Result<Value, String> {
-    log::info!("Fetching weather forecast for lat: {}, lon: {}", lat, lon);
-
-    let url = format!(
-        "https://jeff-ai-cf-be.mrboutte21.workers.dev/api/weather?lat={}&lon={}",
-        lat, lon
-    );
-
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&url)
-        .header("Authorization", format!("Bearer {}", token))
-        .header("Content-Type", "application/json")
-        .send()
-        .await
-        .map_err(|e| {
-            log::error!("Failed to fetch weather forecast: {}", e);
-            e.to_string()
-        })?;
+) -> Result<WeatherResponse, AppError> {
+    log::info!("Fetching weather forecast for: {}", logging::content(&(lat, lon)));
 
-    let json_value = response.json::<Value>().await.map_err(|e| {
-        log::error!("Failed to parse response as JSON: {}", e);
-        e.to_string()
+    let forecast = state.api().await?.weather_forecast(lat, lon).await.map_err(|e| {
+        log::error!("Failed to fetch weather forecast: {}", e);
+        AppError::from(e)
     })?;
 
-    Ok(json_value)
+    Ok(forecast)
 }
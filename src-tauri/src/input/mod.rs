@@ -0,0 +1,5 @@
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+mod push_to_talk;
+
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+pub use push_to_talk::*;
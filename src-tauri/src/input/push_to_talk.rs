@@ -0,0 +1,161 @@
+use crate::handlers::{begin_recording, discard_recording, finish_capture, transcribe_recording};
+use crate::state::AppState;
+use crate::AppHandle;
+use rdev::{listen, Event, EventType, Key};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::Once;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, EventTarget, Manager};
+
+// rdev's listener can't be stopped once running, so it's started at most once
+// and checks the preferences on every key event instead
+static LISTENER: Once = Once::new();
+
+enum Action {
+    Start,
+    Stop(Duration),
+}
+
+/// Maps a key name from the preferences to an rdev key. Names follow rdev's
+/// `Key` variants, e.g. "AltGr", "ControlRight", "F8".
+pub fn parse_key(name: &str) -> Option<Key> {
+    let key = match name {
+        "Alt" => Key::Alt,
+        "AltGr" => Key::AltGr,
+        "ControlLeft" => Key::ControlLeft,
+        "ControlRight" => Key::ControlRight,
+        "ShiftLeft" => Key::ShiftLeft,
+        "ShiftRight" => Key::ShiftRight,
+        "MetaLeft" => Key::MetaLeft,
+        "MetaRight" => Key::MetaRight,
+        "CapsLock" => Key::CapsLock,
+        "Function" => Key::Function,
+        "Space" => Key::Space,
+        "Pause" => Key::Pause,
+        "ScrollLock" => Key::ScrollLock,
+        "PrintScreen" => Key::PrintScreen,
+        "F1" => Key::F1,
+        "F2" => Key::F2,
+        "F3" => Key::F3,
+        "F4" => Key::F4,
+        "F5" => Key::F5,
+        "F6" => Key::F6,
+        "F7" => Key::F7,
+        "F8" => Key::F8,
+        "F9" => Key::F9,
+        "F10" => Key::F10,
+        "F11" => Key::F11,
+        "F12" => Key::F12,
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// Starts the global key listener if push-to-talk hasn't been started yet.
+/// Holding the configured key records, releasing it stops the recording and
+/// transcribes it with refinement. Taps shorter than the minimum duration are
+/// discarded.
+pub fn ensure_push_to_talk_listener(app_handle: &AppHandle) {
+    LISTENER.call_once(|| {
+        let (sender, receiver) = channel();
+
+        let worker_handle = app_handle.clone();
+        std::thread::spawn(move || run_worker(worker_handle, receiver));
+
+        let listener_handle = app_handle.clone();
+        std::thread::spawn(move || {
+            let mut pressed_at: Option<Instant> = None;
+
+            let result = listen(move |event: Event| {
+                let (key, pressed) = match event.event_type {
+                    EventType::KeyPress(key) => (key, true),
+                    EventType::KeyRelease(key) => (key, false),
+                    _ => return,
+                };
+
+                let state = listener_handle.state::<AppState>();
+                let target = match state.preferences.lock() {
+                    Ok(preferences) if preferences.push_to_talk.enabled => parse_key(&preferences.push_to_talk.key),
+                    _ => None,
+                };
+                if target != Some(key) {
+                    return;
+                }
+
+                // Ignore auto-repeat while the key is held
+                if pressed {
+                    if pressed_at.is_none() {
+                        pressed_at = Some(Instant::now());
+                        let _ = sender.send(Action::Start);
+                    }
+                } else if let Some(started) = pressed_at.take() {
+                    let _ = sender.send(Action::Stop(started.elapsed()));
+                }
+            });
+
+            if let Err(e) = result {
+                log::error!("Push-to-talk listener failed: {:?}", e);
+            }
+        });
+
+        log::info!("Push-to-talk listener started");
+    });
+}
+
+// Runs start/stop actions one at a time off the listener thread, so a slow
+// device open never blocks key events and a release can't overtake its press
+fn run_worker(app_handle: AppHandle, receiver: Receiver<Action>) {
+    let mut recording = false;
+
+    for action in receiver {
+        let state = app_handle.state::<AppState>();
+
+        match action {
+            Action::Start => match begin_recording(&state) {
+                Ok(()) => {
+                    recording = true;
+                    let _ = app_handle.emit_to(EventTarget::any(), "push-to-talk-started", ());
+                }
+                Err(e) => log::warn!("Push-to-talk could not start recording: {}", e),
+            },
+            Action::Stop(held_for) => {
+                if !recording {
+                    continue;
+                }
+                recording = false;
+
+                let min_duration = state
+                    .preferences
+                    .lock()
+                    .map(|p| Duration::from_millis(p.push_to_talk.min_duration_ms as u64))
+                    .unwrap_or_default();
+
+                if held_for < min_duration {
+                    log::info!("Push-to-talk released after {:?}, discarding", held_for);
+                    if let Err(e) = tauri::async_runtime::block_on(discard_recording(&state)) {
+                        log::error!("Failed to discard recording: {}", e);
+                    }
+                    let _ = app_handle.emit_to(EventTarget::any(), "push-to-talk-discarded", ());
+                } else {
+                    let _ = app_handle.emit_to(EventTarget::any(), "push-to-talk-stopped", ());
+
+                    // Only the stop is sequenced with the other actions, the
+                    // upload runs on its own so the key can be pressed again
+                    match tauri::async_runtime::block_on(finish_capture(&state)) {
+                        Ok(Some(temp_file)) => {
+                            let handle = app_handle.clone();
+                            tauri::async_runtime::spawn(async move {
+                                let state = handle.state::<AppState>();
+                                if let Err(e) = transcribe_recording(&state, temp_file, true).await {
+                                    log::error!("Push-to-talk transcription failed: {}", e);
+                                }
+                            });
+                        }
+                        Ok(None) => {}
+                        Err(e) => log::error!("Failed to stop push-to-talk recording: {}", e),
+                    }
+                }
+            }
+        }
+    }
+}
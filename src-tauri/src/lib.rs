@@ -3,64 +3,50 @@ use dotenv::dotenv;
 use std::sync::atomic::AtomicBool;
 use std::sync::{Arc, Mutex};
 
+pub mod api;
 mod audio;
+mod auth;
+mod delivery;
+mod error;
 mod handlers;
-mod models;
+mod input;
+mod logging;
+#[cfg(feature = "mock-backend")]
+pub mod mock;
+pub mod models;
+mod profile;
 mod state;
+mod tasks;
 
+use audio::PreRoll;
+use auth::{SecretStore, SessionStore};
 use handlers::*;
-use state::{AppState, RecordingState};
+use models::{BackendConfig, Preferences};
+use profile::{ProfilePaths, ProfileRegistry};
+use state::{AppState, RecordingState, StoredUser};
+use tasks::TaskStore;
 use tauri::Manager;
-use tauri::Listener;
-use tauri_plugin_clipboard_manager::ClipboardExt;
-use std::{thread, time::Duration};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 
-// Only include rdev on desktop platforms
-#[cfg(not(any(target_os = "android", target_os = "ios")))]
-use rdev::{simulate, EventType, Key, SimulateError};
+/// The runtime the app runs on. The integration tests swap in Tauri's mock
+/// runtime, which needs no display.
+#[cfg(not(feature = "test-runtime"))]
+pub type AppRuntime = tauri::Wry;
+#[cfg(feature = "test-runtime")]
+pub type AppRuntime = tauri::test::MockRuntime;
 
-#[cfg(any(target_os = "macos", target_os = "windows"))]
-use tauri_plugin_global_shortcut;
-
-#[cfg(any(target_os = "macos", target_os = "windows"))]
-use tauri_plugin_updater;
-
-#[cfg(not(any(target_os = "android", target_os = "ios")))]
-fn simulate_paste() {
-    // Helper function to send a key event
-    fn send(event_type: EventType) {
-        if let Err(SimulateError) = simulate(&event_type) {
-            eprintln!("Failed to send {:?}", event_type);
-        }
-        // Introduce a small delay between events to ensure they are registered
-        thread::sleep(Duration::from_millis(20));
-    }
+type AppHandle = tauri::AppHandle<AppRuntime>;
 
-    #[cfg(target_os = "macos")]
-    {
-        // macOS uses the Command key for shortcuts
-        send(EventType::KeyPress(Key::MetaLeft));
-        send(EventType::KeyPress(Key::KeyV));
-        send(EventType::KeyRelease(Key::KeyV));
-        send(EventType::KeyRelease(Key::MetaLeft));
-    }
+// Lifetime of the session the mock backend starts with
+#[cfg(feature = "mock-backend")]
+const MOCK_SESSION_SECS: i64 = 30 * 24 * 60 * 60;
 
-    #[cfg(not(target_os = "macos"))]
-    {
-        // Windows and Linux typically use the Control key for shortcuts
-        send(EventType::KeyPress(Key::ControlLeft));
-        send(EventType::KeyPress(Key::KeyV));
-        send(EventType::KeyRelease(Key::KeyV));
-        send(EventType::KeyRelease(Key::ControlLeft));
-    }
-}
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+use tauri_plugin_global_shortcut;
 
-// Add a no-op version for mobile platforms
-#[cfg(any(target_os = "android", target_os = "ios"))]
-fn simulate_paste() {
-    // No-op on mobile platforms
-    log::warn!("Paste simulation is not supported on mobile platforms");
-}
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+use tauri_plugin_updater;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -68,10 +54,10 @@ pub fn run() {
     // Load environment variables from .env file
     dotenv().ok();
 
-    let mut builder = tauri::Builder::default();
+    let mut builder = tauri::Builder::<AppRuntime>::default();
 
     // Conditionally add the plugins
-    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
     {
         builder = builder
             .plugin(tauri_plugin_global_shortcut::Builder::new().build())
@@ -80,46 +66,100 @@ pub fn run() {
 
     builder
         .setup(|app| {
-            let app_state = AppState {
-                user: Mutex::new(None),
-                existing_user: Mutex::new(None),
-                temp_file: Arc::new(Mutex::new(None)),
-                recording_state: Mutex::new(RecordingState::Stopped),
-                is_recording: Arc::new(AtomicBool::new(false)),
-                audio_writer: Mutex::new(None),
-                recording_sender: Arc::new(Mutex::new(None)),
-                app_handle: app.handle().clone(),
-                original_volume: Arc::new(Mutex::new(None)),
-                audio_device_id: Arc::new(Mutex::new(None)),
+            let config_dir = app.path().app_config_dir()?;
+            let registry = ProfileRegistry::load(&config_dir.join("profiles.json"));
+
+            // `JEFF_AI_ENV=mock` serves the API from memory and runs under its
+            // own profile, so the real session is never touched
+            #[cfg(feature = "mock-backend")]
+            let mock_backend = mock::start_for_app()?;
+            #[cfg(feature = "mock-backend")]
+            let profile_id = match mock_backend {
+                Some(_) => mock::MOCK_ENVIRONMENT,
+                None => registry.active.as_str(),
             };
-            app.manage(app_state);
+            #[cfg(not(feature = "mock-backend"))]
+            let profile_id = registry.active.as_str();
+
+            let paths = ProfilePaths::new(&config_dir, &app.path().app_data_dir()?, profile_id);
+            log::info!("Using profile {}", paths.id);
 
-            let app_handle = app.handle().clone();
-            app.listen("refined-transcription-complete", move |event| {
-                log::info!("Refined transcription completed: {:?}", event.payload());
+            // Preferences used to live directly in the config dir, carry them
+            // over into the default profile the first time it's loaded
+            if let Some(legacy_dir) = &paths.legacy_dir {
+                let legacy_preferences = legacy_dir.join("preferences.json");
+                if legacy_preferences.exists() && !paths.preferences().exists() {
+                    let migrated = std::fs::create_dir_all(&paths.config_dir)
+                        .and_then(|_| std::fs::rename(&legacy_preferences, paths.preferences()));
+                    if let Err(e) = migrated {
+                        log::warn!("Failed to move preferences into profile {}: {}", paths.id, e);
+                    }
+                }
+            }
 
-                // Trim quotes from the payload if present
-                let payload = event.payload();
-                let trimmed_payload = payload.trim_matches('"');
+            let preferences = Preferences::load(&paths.preferences());
+            logging::set_log_content(preferences.diagnostics.log_content);
+            logging::spawn_log_pruning(app.path().app_log_dir()?);
+            let backend = BackendConfig::resolve(&preferences.backend);
+            log::info!("Using backend {}", backend.base_url);
 
-                // get previous clipboard content
-                let previous_clipboard = app_handle.clipboard().read_text().unwrap_or_default();
-                log::info!("Previous clipboard content: {:?}", previous_clipboard);
+            // Restore the signed-in user from the last run, so recording and
+            // push-to-talk work before the frontend has finished loading
+            let secrets = Arc::new(SecretStore::for_profile(&paths));
+            let stored_user = StoredUser::load(&secrets);
+            let session = SessionStore::restore(Arc::clone(&secrets));
 
-                // Get the trimmed payload and write to clipboard
-                if let Err(e) = app_handle.clipboard().write_text(trimmed_payload) {
-                    log::error!("Error writing to clipboard: {:?}", e);
-                    return;
+            #[cfg(feature = "mock-backend")]
+            let (stored_user, backend) = match mock_backend {
+                Some(mock_backend) => {
+                    app.manage(mock_backend);
+                    tauri::async_runtime::block_on(session.set(auth::Session::new(
+                        mock::MOCK_ACCESS_TOKEN.to_string(),
+                        None,
+                        Some(MOCK_SESSION_SECS),
+                    )));
+                    let (user, existing_user) = mock::mock_user();
+                    let stored_user = StoredUser {
+                        user: Some(user),
+                        existing_user: Some(existing_user),
+                    };
+                    (stored_user, BackendConfig { mock: true, ..backend })
                 }
+                None => (stored_user, backend),
+            };
 
-                // paste the text
-                simulate_paste();
+            let app_state = app_state(
+                app.handle().clone(),
+                paths,
+                preferences,
+                backend,
+                secrets,
+                session,
+                stored_user,
+            )?;
+            app.manage(app_state);
+            auth::spawn_refresh_loop(app.handle().clone());
+            tasks::spawn_sync_loop(app.handle().clone());
+            tasks::spawn_reminder_loop(app.handle().clone());
 
-                // write the previous clipboard content back to the clipboard
-                if let Err(e) = app_handle.clipboard().write_text(previous_clipboard) {
-                    log::error!("Error writing to clipboard: {:?}", e);
+            #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+            {
+                let push_to_talk = app
+                    .state::<AppState>()
+                    .preferences
+                    .lock()
+                    .map(|p| p.push_to_talk.enabled)
+                    .unwrap_or(false);
+                if push_to_talk {
+                    input::ensure_push_to_talk_listener(app.handle());
                 }
-            });
+            }
+
+            // Global shortcuts and rdev's key listener both go through X11
+            #[cfg(target_os = "linux")]
+            if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+                log::warn!("Running under Wayland: global shortcuts and push-to-talk only see XWayland windows");
+            }
 
             Ok(())
         })
@@ -130,6 +170,18 @@ pub fn run() {
                         file_name: Some("app.log".to_string()),
                     },
                 ))
+                .max_file_size(logging::MAX_LOG_FILE_BYTES)
+                .rotation_strategy(tauri_plugin_log::RotationStrategy::KeepAll)
+                // Every line goes through the redaction policy before it hits disk
+                .format(|out, message, record| {
+                    out.finish(format_args!(
+                        "[{}][{}][{}] {}",
+                        chrono::Local::now().format("%Y-%m-%d][%H:%M:%S"),
+                        record.target(),
+                        record.level(),
+                        logging::scrub(&message.to_string())
+                    ))
+                })
                 .build(),
         )
         .plugin(tauri_plugin_clipboard_manager::init())
@@ -138,24 +190,145 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_shell::init())
-        .invoke_handler(tauri::generate_handler![
-            get_all_refinements,
-            refine_text,
-            convert_to_markdown,
-            capture_user,
-            set_user,
-            start_recording,
-            stop_recording,
-            fetch_tasks,
-            create_task,
-            update_task,
-            delete_task,
-            get_weather_location,
-            get_weather_forecast,
-            get_user_settings,
-            create_user_settings,
-            update_user_settings,
-        ])
+        .invoke_handler(invoke_handler())
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+fn invoke_handler() -> impl Fn(tauri::ipc::Invoke<AppRuntime>) -> bool + Send + Sync + 'static {
+    tauri::generate_handler![
+        get_all_refinements,
+        refine_text,
+        convert_to_markdown,
+        capture_user,
+        set_user,
+        start_recording,
+        stop_recording,
+        fetch_tasks,
+        create_task,
+        update_task,
+        delete_task,
+        parse_tasks,
+        list_task_occurrences,
+        set_occurrence_completed,
+        snooze_task_reminder,
+        get_weather_location,
+        get_weather_forecast,
+        get_user_settings,
+        create_user_settings,
+        update_user_settings,
+        get_preferences,
+        update_preferences,
+        set_session,
+        logout,
+        get_backend_config,
+        append_journal_entry,
+        list_profiles,
+        create_profile,
+        switch_profile,
+        delete_profile,
+    ]
+}
+
+// Everything the commands share, for the profile in `paths`
+fn app_state(
+    app_handle: AppHandle,
+    paths: ProfilePaths,
+    preferences: Preferences,
+    backend: BackendConfig,
+    secrets: Arc<SecretStore>,
+    session: SessionStore,
+    stored_user: StoredUser,
+) -> Result<AppState, Box<dyn std::error::Error>> {
+    let pre_roll = preferences
+        .audio
+        .pre_roll
+        .then(|| PreRoll::start(Duration::from_millis(preferences.audio.pre_roll_ms as u64)));
+
+    // Per-request timeouts are set by the API client, this only stops
+    // an unreachable backend from stalling the connect phase
+    let http_client = reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(10))
+        .build()?;
+    let task_store = TaskStore::open(&paths.task_db())?;
+
+    Ok(AppState {
+        user: Mutex::new(stored_user.user),
+        existing_user: Mutex::new(stored_user.existing_user),
+        session,
+        secrets: Mutex::new(secrets),
+        profile: Mutex::new(paths),
+        work: Mutex::new(CancellationToken::new()),
+        tasks: Mutex::new(Arc::new(task_store)),
+        sync_requested: Default::default(),
+        reminders_changed: Default::default(),
+        preferences: Mutex::new(preferences),
+        backend: Mutex::new(backend),
+        http_client,
+        temp_file: Arc::new(Mutex::new(None)),
+        recording_state: Mutex::new(RecordingState::Stopped),
+        is_recording: Arc::new(AtomicBool::new(false)),
+        recording_sender: Arc::new(Mutex::new(None)),
+        recording_thread: Mutex::new(None),
+        pre_roll: Mutex::new(pre_roll),
+        app_handle,
+        original_volume: Arc::new(Mutex::new(None)),
+        audio_device_id: Arc::new(Mutex::new(None)),
+    })
+}
+
+/// Builds the app on Tauri's mock runtime with the same commands and state
+/// as `run`, pointed at `backend` and signed in as the mock user. The
+/// profile's files go in `dir`. From then on every secret the process saves
+/// stays in memory. Of the background loops only task sync runs, and no
+/// plugins are installed.
+#[cfg(feature = "test-runtime")]
+pub fn build_mock_app(
+    builder: tauri::Builder<AppRuntime>,
+    backend: &mock::MockBackend,
+    dir: &std::path::Path,
+) -> Result<tauri::App<AppRuntime>, Box<dyn std::error::Error>> {
+    let app = builder
+        .invoke_handler(invoke_handler())
+        .build(tauri::test::mock_context(tauri::test::noop_assets()))?;
+
+    // Keep the mock session out of the developer's Keychain or Secret
+    // Service. Mock credentials live only in memory, and since saving to them
+    // succeeds no encrypted fallback file is written either.
+    keyring::set_default_credential_builder(keyring::mock::default_credential_builder());
+
+    let paths = ProfilePaths::new(dir, dir, mock::MOCK_ENVIRONMENT);
+    let secrets = Arc::new(SecretStore::for_profile(&paths));
+    let session = SessionStore::restore(Arc::clone(&secrets));
+    tauri::async_runtime::block_on(session.set(auth::Session::new(
+        mock::MOCK_ACCESS_TOKEN.to_string(),
+        None,
+        Some(MOCK_SESSION_SECS),
+    )));
+
+    let environment = models::BackendEnvironment::Custom(backend.url());
+    let config = BackendConfig {
+        base_url: environment.base_url()?,
+        environment,
+        from_env: false,
+        mock: true,
+    };
+    let (user, existing_user) = mock::mock_user();
+    let stored_user = StoredUser {
+        user: Some(user),
+        existing_user: Some(existing_user),
+    };
+
+    let state = app_state(
+        app.handle().clone(),
+        paths,
+        Preferences::default(),
+        config,
+        secrets,
+        session,
+        stored_user,
+    )?;
+    app.manage(state);
+    tasks::spawn_sync_loop(app.handle().clone());
+    Ok(app)
+}
@@ -0,0 +1,131 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+// Size at which app.log is rotated
+pub const MAX_LOG_FILE_BYTES: u128 = 5 * 1024 * 1024;
+
+// Rotated logs kept next to the current one, older ones are deleted
+const ROTATED_LOGS_KEPT: usize = 4;
+
+// How often old logs are looked for. The plugin has no hook for when it
+// rotates, so this bounds how many extra files a busy session can pile up.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+// Off unless the user opts in from the preferences, see `set_log_content`
+static LOG_CONTENT: AtomicBool = AtomicBool::new(false);
+
+/// Allows dictated text, clipboard data and other personal content to reach
+/// the log. Meant for debugging only.
+pub fn set_log_content(enabled: bool) {
+    if LOG_CONTENT.swap(enabled, Ordering::Relaxed) != enabled {
+        log::warn!(
+            "Logging of personal content {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+    }
+}
+
+fn log_content() -> bool {
+    LOG_CONTENT.load(Ordering::Relaxed)
+}
+
+/// Wraps personal content (transcripts, clipboard data, task text, locations)
+/// for logging. Only its length is written unless content logging is on.
+pub fn content<T: fmt::Debug + ?Sized>(value: &T) -> Redacted<'_, T> {
+    Redacted(value)
+}
+
+pub struct Redacted<'a, T: ?Sized>(&'a T);
+
+impl<T: fmt::Debug + ?Sized> fmt::Display for Redacted<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if log_content() {
+            return write!(f, "{:?}", self.0);
+        }
+        let len = format!("{:?}", self.0).chars().count();
+        write!(f, "<redacted, {} chars>", len)
+    }
+}
+
+/// Last line of defence, run on every log line before it's written: masks
+/// bearer tokens and JWTs always, and email addresses unless content logging
+/// is on.
+pub fn scrub(line: &str) -> String {
+    let is_word = |c: char| c.is_ascii_alphanumeric() || "._%+-@/=~".contains(c);
+    let mask_emails = !log_content();
+
+    let mut out = String::with_capacity(line.len());
+    let mut previous_word = "";
+    let mut rest = line;
+
+    while let Some(start) = rest.find(is_word) {
+        out.push_str(&rest[..start]);
+        let after = &rest[start..];
+        let end = after.find(|c: char| !is_word(c)).unwrap_or(after.len());
+        let word = &after[..end];
+
+        if previous_word.eq_ignore_ascii_case("bearer") || is_jwt(word) {
+            out.push_str("<token>");
+        } else if mask_emails && is_email(word) {
+            out.push_str("<email>");
+        } else {
+            out.push_str(word);
+        }
+
+        previous_word = word;
+        rest = &after[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn is_jwt(word: &str) -> bool {
+    word.starts_with("eyJ") && word.matches('.').count() == 2
+}
+
+fn is_email(word: &str) -> bool {
+    match word.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty() && domain.contains('.') && !domain.starts_with('.') && !domain.ends_with('.')
+        }
+        None => false,
+    }
+}
+
+/// Prunes rotated logs now and then for as long as the app runs, not only at
+/// startup, since the app can stay open in the tray for weeks.
+pub fn spawn_log_pruning(log_dir: PathBuf) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            prune_rotated_logs(&log_dir);
+            tokio::time::sleep(PRUNE_INTERVAL).await;
+        }
+    });
+}
+
+/// Deletes all but the newest rotated logs. The log plugin rotates app.log
+/// into `app_<timestamp>.log` files but never removes them.
+fn prune_rotated_logs(log_dir: &Path) {
+    let Ok(entries) = fs::read_dir(log_dir) else {
+        return;
+    };
+
+    let mut rotated: Vec<_> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.starts_with("app_") && name.ends_with(".log")
+        })
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .collect();
+
+    rotated.sort_by(|a, b| b.0.cmp(&a.0));
+    for (_, path) in rotated.into_iter().skip(ROTATED_LOGS_KEPT) {
+        if let Err(e) = fs::remove_file(&path) {
+            log::warn!("Failed to delete old log {:?}: {}", path, e);
+        }
+    }
+}
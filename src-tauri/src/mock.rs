@@ -0,0 +1,604 @@
+//! In-process stand-in for the Jeff AI worker, so the app and the integration
+//! tests can run without a network connection. Only built with the
+//! `mock-backend` feature.
+//!
+//! Every endpoint the app calls is served from memory with canned but
+//! well-formed data. Writes behave like the real worker: tasks and settings
+//! persist for the lifetime of the server, and a repeated `Idempotency-Key`
+//! gets the original response back instead of applying the write twice.
+
+use crate::api::{RefineTextResponse, Refinement, TaskRecord, Units, UserSettings};
+use crate::models::{ExistingUser, TaskId, User, API_URL_ENV, ENVIRONMENT_ENV};
+use axum::extract::{Multipart, Path, Query, Request, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, patch, post};
+use axum::{Json, Router};
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+/// Transcript returned for any upload that contains sound.
+pub const MOCK_TRANSCRIPTION: &str = "this is a mock transcription";
+/// Refined version of `MOCK_TRANSCRIPTION`.
+pub const MOCK_REFINED: &str = "This is a mock transcription.";
+
+/// `JEFF_AI_ENV` value that points the app at an in-process mock backend.
+pub const MOCK_ENVIRONMENT: &str = "mock";
+
+/// Bearer token the app uses against the mock, which accepts any token.
+pub const MOCK_ACCESS_TOKEN: &str = "mock-access-token";
+
+/// Starts a mock backend when the app was launched with `JEFF_AI_ENV=mock`,
+/// and points the backend override at it. Keep the returned value alive for
+/// as long as the app runs.
+pub fn start_for_app() -> std::io::Result<Option<MockBackend>> {
+    let requested = std::env::var(ENVIRONMENT_ENV).is_ok_and(|name| name.trim().eq_ignore_ascii_case(MOCK_ENVIRONMENT));
+    if !requested {
+        return Ok(None);
+    }
+
+    let backend = tauri::async_runtime::block_on(MockBackend::start())?;
+    std::env::set_var(API_URL_ENV, backend.url());
+    log::warn!("Using the mock backend at {}, nothing is sent to a real one", backend.url());
+    Ok(Some(backend))
+}
+
+/// Signed-in user the app starts with when running against the mock, so no
+/// Kinde login is needed.
+pub fn mock_user() -> (User, ExistingUser) {
+    let user = User {
+        id: "kp_mock".to_string(),
+        email: "dev@example.com".to_string(),
+        family_name: "Developer".to_string(),
+        given_name: "Mock".to_string(),
+    };
+    let existing_user = ExistingUser {
+        id: "mock-user".to_string(),
+        auth_user_id: user.id.clone(),
+        email: Some(user.email.clone()),
+        display_name: Some("Mock Developer".to_string()),
+        created_at: None,
+        updated_at: None,
+    };
+    (user, existing_user)
+}
+
+#[derive(Default)]
+struct MockData {
+    user: Option<ExistingUser>,
+    tasks: Vec<TaskRecord>,
+    next_task_id: u32,
+    refinements: Vec<Refinement>,
+    settings: Option<UserSettings>,
+    /// Responses already sent for an `Idempotency-Key`
+    replies: HashMap<String, (StatusCode, Value)>,
+    /// Failures to inject into the next requests, oldest first
+    failures: VecDeque<Failure>,
+    /// Paths of every request received, including failed ones
+    requests: Vec<String>,
+}
+
+#[derive(Clone, Copy)]
+enum Failure {
+    /// Fail without handling the request
+    Before(u16),
+    /// Handle the request, then answer with an error as if the response got
+    /// lost on the way back
+    After(u16),
+}
+
+type Shared = Arc<Mutex<MockData>>;
+
+/// A running mock backend. The server shuts down when this is dropped.
+pub struct MockBackend {
+    addr: SocketAddr,
+    data: Shared,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl MockBackend {
+    /// Starts the server on a free port on localhost.
+    pub async fn start() -> std::io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+        let data = Shared::default();
+        let (shutdown, shutdown_receiver) = oneshot::channel::<()>();
+
+        let app = router(Arc::clone(&data));
+        tokio::spawn(async move {
+            let server = axum::serve(listener, app).with_graceful_shutdown(async {
+                let _ = shutdown_receiver.await;
+            });
+            if let Err(e) = server.await {
+                log::error!("Mock backend stopped: {}", e);
+            }
+        });
+
+        log::info!("Mock backend listening on {}", addr);
+        Ok(Self {
+            addr,
+            data,
+            shutdown: Some(shutdown),
+        })
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Makes the next request fail with `status` before it reaches a handler,
+    /// e.g. 503 to exercise retries. Queued failures are used up in order.
+    pub fn fail_next(&self, status: u16) {
+        self.lock().failures.push_back(Failure::Before(status));
+    }
+
+    /// Lets the next request through, then answers it with `status` anyway,
+    /// like a response lost on the way back. Any write still takes effect.
+    pub fn fail_next_after_handling(&self, status: u16) {
+        self.lock().failures.push_back(Failure::After(status));
+    }
+
+    /// How many requests were made to `path`, counting failed attempts.
+    pub fn request_count(&self, path: &str) -> usize {
+        self.lock().requests.iter().filter(|p| p.as_str() == path).count()
+    }
+
+    pub fn tasks(&self) -> Vec<TaskRecord> {
+        self.lock().tasks.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockData> {
+        self.data.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for MockBackend {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+fn router(data: Shared) -> Router {
+    Router::new()
+        .route("/api/capture", post(capture_user))
+        .route("/api/transcribe", post(transcribe))
+        .route("/api/tasks", get(list_tasks).post(create_task))
+        .route("/api/tasks/:id", patch(update_task).delete(delete_task))
+        .route("/api/refinements", get(list_refinements).post(refine_text))
+        .route("/api/refinements/convert-to-markdown", post(convert_to_markdown))
+        .route("/api/settings", get(get_settings).post(create_settings).patch(update_settings))
+        .route("/api/weather/geocode", get(geocode))
+        .route("/api/weather", get(weather_forecast))
+        .layer(middleware::from_fn_with_state(Arc::clone(&data), gatekeeper))
+        .with_state(data)
+}
+
+fn lock(data: &Shared) -> std::sync::MutexGuard<'_, MockData> {
+    data.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({ "error": message }))).into_response()
+}
+
+fn now() -> String {
+    Utc::now().to_rfc3339()
+}
+
+/// Records the request, applies injected failures, rejects requests without
+/// a bearer token like the worker does, and replays idempotent writes.
+async fn gatekeeper(State(data): State<Shared>, headers: HeaderMap, request: Request, next: Next) -> Response {
+    let key = headers
+        .get("Idempotency-Key")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    let failure = {
+        let mut data = lock(&data);
+        data.requests.push(request.uri().path().to_string());
+
+        let failure = data.failures.pop_front();
+        if let Some(Failure::Before(status)) = failure {
+            return injected(status);
+        }
+
+        if let Some((status, body)) = key.as_ref().and_then(|key| data.replies.get(key)) {
+            return (*status, Json(body.clone())).into_response();
+        }
+        failure
+    };
+
+    let authorized = headers
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("Bearer ") && value.len() > "Bearer ".len());
+    if !authorized {
+        return error(StatusCode::UNAUTHORIZED, "Unauthorized");
+    }
+
+    let response = next.run(request).await;
+    let Some(key) = key else {
+        return match failure {
+            Some(Failure::After(status)) => injected(status),
+            _ => response,
+        };
+    };
+
+    // Keep the body so a retry with the same key gets the same answer
+    let status = response.status();
+    let bytes = match axum::body::to_bytes(response.into_body(), usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(_) => return error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to read response"),
+    };
+    let body: Value = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    lock(&data).replies.insert(key, (status, body.clone()));
+
+    match failure {
+        Some(Failure::After(status)) => injected(status),
+        _ => (status, Json(body)).into_response(),
+    }
+}
+
+fn injected(status: u16) -> Response {
+    let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    error(status, "Injected failure")
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CaptureUserBody {
+    auth_user_id: String,
+    email: String,
+    display_name: String,
+}
+
+async fn capture_user(State(data): State<Shared>, Json(body): Json<CaptureUserBody>) -> Response {
+    let mut data = lock(&data);
+    let user = data
+        .user
+        .get_or_insert_with(|| ExistingUser {
+            id: mock_user().1.id,
+            auth_user_id: body.auth_user_id.clone(),
+            email: Some(body.email.clone()),
+            display_name: Some(body.display_name.clone()),
+            created_at: Some(now()),
+            updated_at: Some(now()),
+        })
+        .clone();
+    Json(json!({ "user": user })).into_response()
+}
+
+async fn transcribe(mut multipart: Multipart) -> Response {
+    let mut audio = Vec::new();
+    let mut refine = false;
+
+    while let Ok(Some(field)) = multipart.next_field().await {
+        let name = field.name().map(str::to_string);
+        match name.as_deref() {
+            Some("file") => audio = field.bytes().await.map(|b| b.to_vec()).unwrap_or_default(),
+            Some("refine") => refine = field.text().await.is_ok_and(|text| text == "true"),
+            _ => {}
+        }
+    }
+
+    if audio.is_empty() {
+        return error(StatusCode::BAD_REQUEST, "No audio file provided");
+    }
+
+    // Past the 44-byte WAV header, all-zero audio counts as silence
+    if audio.iter().skip(44).all(|&byte| byte == 0) {
+        return Json(json!({ "message": "No dialog detected" })).into_response();
+    }
+
+    let mut body = json!({ "transcription": MOCK_TRANSCRIPTION });
+    if refine {
+        body["refined"] = json!(MOCK_REFINED);
+    }
+    Json(body).into_response()
+}
+
+async fn list_tasks(State(data): State<Shared>) -> Response {
+    Json(lock(&data).tasks.clone()).into_response()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateTaskBody {
+    task: String,
+    done: bool,
+    user_id: String,
+    assigned_date: String,
+}
+
+async fn create_task(State(data): State<Shared>, Json(body): Json<CreateTaskBody>) -> Response {
+    if body.task.trim().is_empty() {
+        return error(StatusCode::BAD_REQUEST, "Task is required");
+    }
+
+    let mut data = lock(&data);
+    data.next_task_id += 1;
+    let task = TaskRecord {
+        id: TaskId::from(data.next_task_id.to_string()),
+        task: body.task,
+        done: body.done,
+        assigned_date: Some(body.assigned_date),
+        user_id: Some(body.user_id),
+        created_at: Some(now()),
+        updated_at: Some(now()),
+    };
+    data.tasks.push(task.clone());
+    (StatusCode::CREATED, Json(task)).into_response()
+}
+
+#[derive(Deserialize)]
+struct UpdateTaskBody {
+    task: Option<String>,
+    done: Option<bool>,
+    day: Option<String>,
+}
+
+async fn update_task(State(data): State<Shared>, Path(id): Path<TaskId>, Json(body): Json<UpdateTaskBody>) -> Response {
+    let mut data = lock(&data);
+    let Some(task) = data.tasks.iter_mut().find(|task| task.id == id) else {
+        return error(StatusCode::NOT_FOUND, "Task not found");
+    };
+
+    if let Some(text) = body.task {
+        task.task = text;
+    }
+    if let Some(done) = body.done {
+        task.done = done;
+    }
+    if let Some(day) = body.day {
+        task.assigned_date = Some(day);
+    }
+    task.updated_at = Some(now());
+    Json(task.clone()).into_response()
+}
+
+async fn delete_task(State(data): State<Shared>, Path(id): Path<TaskId>) -> Response {
+    let mut data = lock(&data);
+    let before = data.tasks.len();
+    data.tasks.retain(|task| task.id != id);
+    if data.tasks.len() == before {
+        return error(StatusCode::NOT_FOUND, "Task not found");
+    }
+    StatusCode::NO_CONTENT.into_response()
+}
+
+async fn list_refinements(State(data): State<Shared>) -> Response {
+    Json(lock(&data).refinements.clone()).into_response()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RefineTextBody {
+    original_text: String,
+    user_id: String,
+}
+
+/// Capitalizes the first letter and makes sure the text ends with a period,
+/// enough to tell refined text apart from the original.
+fn mock_refine(text: &str) -> String {
+    let text = text.trim();
+    let mut chars = text.chars();
+    let mut refined: String = match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    };
+    if !refined.ends_with(['.', '!', '?']) {
+        refined.push('.');
+    }
+    refined
+}
+
+async fn refine_text(State(data): State<Shared>, Json(body): Json<RefineTextBody>) -> Response {
+    if body.original_text.trim().is_empty() {
+        return error(StatusCode::BAD_REQUEST, "Text is required");
+    }
+
+    let refined_text = mock_refine(&body.original_text);
+    let mut data = lock(&data);
+    let id = (data.refinements.len() + 1).to_string();
+    data.refinements.push(Refinement {
+        id,
+        user_id: body.user_id,
+        original_text_word_count: body.original_text.split_whitespace().count() as u32,
+        original_text: body.original_text,
+        refined_text_word_count: refined_text.split_whitespace().count() as u32,
+        refined_text: refined_text.clone(),
+        explanation: Some("Mock refinement".to_string()),
+        vector: Vec::new(),
+        created_at: now(),
+        updated_at: now(),
+    });
+
+    Json(RefineTextResponse {
+        refined_text,
+        explanation: Some("Mock refinement".to_string()),
+    })
+    .into_response()
+}
+
+#[derive(Deserialize)]
+struct MarkdownBody {
+    html: String,
+}
+
+async fn convert_to_markdown(Json(body): Json<MarkdownBody>) -> Response {
+    // Strip the tags, the real conversion isn't worth reimplementing here
+    let mut markdown = String::new();
+    let mut in_tag = false;
+    for c in body.html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => markdown.push(c),
+            _ => {}
+        }
+    }
+    Json(json!({ "markdown": markdown.trim() })).into_response()
+}
+
+async fn get_settings(State(data): State<Shared>) -> Response {
+    match lock(&data).settings.clone() {
+        Some(settings) => Json(settings).into_response(),
+        None => error(StatusCode::NOT_FOUND, "Settings not found"),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsBody {
+    user_id: String,
+    lat: Option<f64>,
+    lon: Option<f64>,
+    city: Option<String>,
+    state: Option<String>,
+    country: Option<String>,
+    units: Units,
+    language: String,
+}
+
+impl SettingsBody {
+    fn into_settings(self) -> UserSettings {
+        UserSettings {
+            id: "mock-settings".to_string(),
+            user_id: self.user_id,
+            lat: self.lat,
+            lon: self.lon,
+            city: self.city,
+            state: self.state,
+            country: self.country,
+            units: self.units,
+            language: self.language,
+        }
+    }
+}
+
+async fn create_settings(State(data): State<Shared>, Json(body): Json<SettingsBody>) -> Response {
+    let mut data = lock(&data);
+    if data.settings.is_some() {
+        return error(StatusCode::CONFLICT, "Settings already exist");
+    }
+    let settings = body.into_settings();
+    data.settings = Some(settings.clone());
+    (StatusCode::CREATED, Json(settings)).into_response()
+}
+
+async fn update_settings(State(data): State<Shared>, Json(body): Json<SettingsBody>) -> Response {
+    let mut data = lock(&data);
+    if data.settings.is_none() {
+        return error(StatusCode::NOT_FOUND, "Settings not found");
+    }
+    let settings = body.into_settings();
+    data.settings = Some(settings.clone());
+    Json(settings).into_response()
+}
+
+#[derive(Deserialize)]
+struct GeocodeQuery {
+    q: String,
+}
+
+async fn geocode(Query(query): Query<GeocodeQuery>) -> Response {
+    let mut parts = query.q.split(',').map(str::trim);
+    let name = parts.next().unwrap_or_default();
+    let state = parts.next().unwrap_or_default();
+    let country = parts.next().unwrap_or_default();
+
+    Json(json!([{
+        "name": name,
+        "lat": 40.7128,
+        "lon": -74.006,
+        "country": country,
+        "state": state,
+        "local_names": { "en": name },
+    }]))
+    .into_response()
+}
+
+#[derive(Deserialize)]
+struct ForecastQuery {
+    lat: f64,
+    lon: f64,
+}
+
+async fn weather_forecast(Query(query): Query<ForecastQuery>) -> Response {
+    let dt = Utc::now().timestamp();
+    let sky = json!([{ "id": 800, "main": "Clear", "description": "clear sky", "icon": "01d" }]);
+    let current = json!({
+        "dt": dt,
+        "sunrise": dt - 6 * 3600,
+        "sunset": dt + 6 * 3600,
+        "temp": 68.0,
+        "feels_like": 67.0,
+        "pressure": 1015,
+        "humidity": 40,
+        "dew_point": 45.0,
+        "uvi": 3.0,
+        "clouds": 0,
+        "visibility": 10000,
+        "wind_speed": 5.0,
+        "wind_deg": 180,
+        "weather": sky,
+    });
+
+    let mut hour = current.clone();
+    hour["pop"] = json!(0.0);
+    let hourly: Vec<Value> = (0..24)
+        .map(|i| {
+            let mut hour = hour.clone();
+            hour["dt"] = json!(dt + i * 3600);
+            hour
+        })
+        .collect();
+
+    let daily: Vec<Value> = (0..7)
+        .map(|i| {
+            json!({
+                "dt": dt + i * 86400,
+                "sunrise": dt - 6 * 3600 + i * 86400,
+                "sunset": dt + 6 * 3600 + i * 86400,
+                "moonrise": dt + i * 86400,
+                "moonset": dt + 12 * 3600 + i * 86400,
+                "moon_phase": 0.5,
+                "summary": "Clear skies all day",
+                "temp": { "day": 68.0, "min": 55.0, "max": 72.0, "night": 58.0, "eve": 65.0, "morn": 56.0 },
+                "feels_like": { "day": 67.0, "night": 57.0, "eve": 64.0, "morn": 55.0 },
+                "pressure": 1015,
+                "humidity": 40,
+                "dew_point": 45.0,
+                "wind_speed": 5.0,
+                "wind_deg": 180,
+                "wind_gust": 8.0,
+                "weather": sky,
+                "clouds": 0,
+                "pop": 0.0,
+                "uvi": 3.0,
+            })
+        })
+        .collect();
+
+    Json(json!({
+        "data": {
+            "lat": query.lat,
+            "lon": query.lon,
+            "timezone": "America/New_York",
+            "timezone_offset": -14400,
+            "current": current,
+            "hourly": hourly,
+            "daily": daily,
+        }
+    }))
+    .into_response()
+}
@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+const PRODUCTION_URL: &str = "https://jeff-ai-cf-be.mrboutte21.workers.dev";
+const LOCAL_URL: &str = "http://localhost:8787";
+
+/// Set to a full URL to point the app at any backend, e.g. a self-hosted one.
+pub const API_URL_ENV: &str = "JEFF_AI_API_URL";
+/// Set to `production`, `staging` or `local` to pick a known backend.
+pub const ENVIRONMENT_ENV: &str = "JEFF_AI_ENV";
+/// URL of the staging backend, read at launch or baked in at build time.
+/// There's no default, each deployment has its own.
+pub const STAGING_URL_ENV: &str = "JEFF_AI_STAGING_URL";
+
+/// Which backend the app talks to. Stored in the preferences file and can be
+/// overridden per launch with `JEFF_AI_API_URL` or `JEFF_AI_ENV`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, TS)]
+#[serde(rename_all = "camelCase", tag = "environment", content = "url")]
+#[ts(export)]
+pub enum BackendEnvironment {
+    /// Also what an environment saved by a newer or older version that this
+    /// one doesn't know reads as
+    #[default]
+    #[serde(other)]
+    Production,
+    /// Whatever `JEFF_AI_STAGING_URL` points at
+    Staging,
+    /// `wrangler dev` on its default port
+    Local,
+    Custom(String),
+}
+
+impl BackendEnvironment {
+    /// The URL requests go to, without a trailing slash. Fails for a custom
+    /// URL the HTTP client can't talk to, or staging when no staging URL is
+    /// configured.
+    pub fn base_url(&self) -> Result<String, String> {
+        match self {
+            Self::Production => Ok(PRODUCTION_URL.to_string()),
+            Self::Local => Ok(LOCAL_URL.to_string()),
+            Self::Staging => {
+                let url = std::env::var(STAGING_URL_ENV)
+                    .ok()
+                    .or_else(|| option_env!("JEFF_AI_STAGING_URL").map(String::from))
+                    .filter(|url| !url.trim().is_empty())
+                    .ok_or_else(|| format!("No staging backend configured, set {}", STAGING_URL_ENV))?;
+                http_url(&url)
+            }
+            Self::Custom(url) => http_url(url),
+        }
+    }
+
+    /// Checks that `base_url` can be worked out.
+    pub fn validate(&self) -> Result<(), String> {
+        self.base_url().map(|_| ())
+    }
+
+    /// Reads the environment override, `JEFF_AI_API_URL` wins over `JEFF_AI_ENV`.
+    pub fn from_env() -> Option<Self> {
+        if let Some(url) = std::env::var(API_URL_ENV).ok().filter(|url| !url.trim().is_empty()) {
+            let environment = Self::Custom(url.trim().to_string());
+            match environment.validate() {
+                Ok(()) => return Some(environment),
+                Err(e) => log::warn!("Ignoring {}: {}", API_URL_ENV, e),
+            }
+        }
+
+        let name = std::env::var(ENVIRONMENT_ENV).ok()?;
+        match name.trim().to_lowercase().as_str() {
+            "production" | "prod" => Some(Self::Production),
+            "staging" => Some(Self::Staging),
+            "local" | "dev" => Some(Self::Local),
+            other => {
+                log::warn!("Unknown {} value {:?}, ignoring", ENVIRONMENT_ENV, other);
+                None
+            }
+        }
+    }
+}
+
+// Trims `url` and checks it's one the HTTP client can talk to
+fn http_url(url: &str) -> Result<String, String> {
+    let url = url.trim();
+    let lower = url.to_ascii_lowercase();
+    match lower.strip_prefix("https://").or_else(|| lower.strip_prefix("http://")) {
+        Some(host) if !host.is_empty() && !host.starts_with('/') => Ok(url.trim_end_matches('/').to_string()),
+        Some(_) => Err(format!("Backend URL {:?} has no host", url)),
+        None => Err(format!("Backend URL {:?} must start with http:// or https://", url)),
+    }
+}
+
+/// The backend in effect, as reported to the settings page.
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct BackendConfig {
+    pub environment: BackendEnvironment,
+    pub base_url: String,
+    /// Set when an environment variable overrides the saved preference, in
+    /// which case changing it in settings has no effect until restart
+    pub from_env: bool,
+    /// Running against the in-process mock backend with a built-in user, the
+    /// frontend skips the Kinde login
+    pub mock: bool,
+}
+
+impl BackendConfig {
+    /// Resolves the backend from the environment, falling back to `saved`.
+    pub fn resolve(saved: &BackendEnvironment) -> Self {
+        let (environment, from_env) = match BackendEnvironment::from_env() {
+            Some(environment) => (environment, true),
+            None => (saved.clone(), false),
+        };
+        // Preferences are checked when they're saved, this catches a file
+        // edited by hand or a staging URL that's gone from the environment
+        let (environment, base_url) = match environment.base_url() {
+            Ok(base_url) => (environment, base_url),
+            Err(e) => {
+                log::warn!("{}, using the production backend", e);
+                (BackendEnvironment::Production, PRODUCTION_URL.to_string())
+            }
+        };
+
+        Self {
+            base_url,
+            environment,
+            from_env,
+            mock: false,
+        }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_urls_need_an_http_scheme_and_host() {
+        let custom = |url: &str| BackendEnvironment::Custom(url.to_string());
+        assert!(custom("https://jeff.example.com").validate().is_ok());
+        assert!(custom("HTTP://localhost:9000/").validate().is_ok());
+        assert!(custom("jeff.example.com").validate().is_err());
+        assert!(custom("ftp://jeff.example.com").validate().is_err());
+        assert!(custom("https://").validate().is_err());
+    }
+
+    #[test]
+    fn custom_urls_are_trimmed() {
+        let environment = BackendEnvironment::Custom(" https://jeff.example.com/ \n".to_string());
+        assert_eq!(environment.base_url().unwrap(), "https://jeff.example.com");
+    }
+
+    #[test]
+    fn saved_environments_keep_their_kind() {
+        let staging: BackendEnvironment = serde_json::from_str(r#"{"environment":"staging"}"#).unwrap();
+        assert_eq!(staging, BackendEnvironment::Staging);
+
+        let unknown: BackendEnvironment = serde_json::from_str(r#"{"environment":"qa"}"#).unwrap();
+        assert_eq!(unknown, BackendEnvironment::Production);
+    }
+}
@@ -1,3 +1,17 @@
+mod backend;
+mod preferences;
+mod recurrence;
+mod task;
 mod user;
 
+pub use backend::{BackendConfig, BackendEnvironment, API_URL_ENV, ENVIRONMENT_ENV};
+pub use preferences::{
+    AudioPreferences, DeliveryMode, DeliveryPreferences, DeliveryRoutes, DiagnosticsPreferences, OutputRoute,
+    Preferences, PushToTalkPreferences,
+};
+pub use recurrence::{Day, Frequency, Recurrence};
+pub use task::{
+    NewTask, Priority, Task, TaskChanges, TaskDraft, TaskId, TaskOccurrence, TaskParser, TaskPreview,
+    TaskReminder,
+};
 pub use user::{ExistingUser, User};
@@ -0,0 +1,176 @@
+use crate::models::BackendEnvironment;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Device-local preferences that only the Rust side acts on. Unlike
+/// `UserSettings` these never leave the machine.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Preferences {
+    pub audio: AudioPreferences,
+    pub push_to_talk: PushToTalkPreferences,
+    pub delivery: DeliveryPreferences,
+    pub backend: BackendEnvironment,
+    pub diagnostics: DiagnosticsPreferences,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DiagnosticsPreferences {
+    /// Write dictated text, clipboard data, task content and locations to
+    /// app.log in full. Off by default, only their length is logged.
+    pub log_content: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AudioPreferences {
+    /// Run the spectral denoiser between the input stream and the WAV writer
+    pub noise_suppression: bool,
+    /// How aggressively noise is removed, from 0.0 (off) to 1.0
+    pub noise_suppression_strength: f32,
+    /// Keep an input stream open in the background and prepend the last
+    /// `pre_roll_ms` of audio to each recording, so the first word after the
+    /// hotkey isn't clipped. While enabled the OS microphone-in-use indicator
+    /// (the orange dot on macOS, the taskbar mic icon on Windows) stays on for
+    /// as long as the app is running, even when not dictating.
+    pub pre_roll: bool,
+    pub pre_roll_ms: u32,
+}
+
+impl Default for AudioPreferences {
+    fn default() -> Self {
+        Self {
+            noise_suppression: false,
+            noise_suppression_strength: 0.5,
+            pre_roll: false,
+            pre_roll_ms: 500,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PushToTalkPreferences {
+    pub enabled: bool,
+    /// Key to hold while dictating, named after rdev's `Key` variants
+    pub key: String,
+    /// Recordings shorter than this are treated as accidental taps and dropped
+    pub min_duration_ms: u32,
+}
+
+impl Default for PushToTalkPreferences {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            key: "AltGr".to_string(),
+            min_duration_ms: 300,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum DeliveryMode {
+    /// Write to the clipboard and simulate Cmd/Ctrl+V
+    #[default]
+    Paste,
+    /// Simulate one keystroke per character, for apps that block pasting
+    Type,
+}
+
+/// Where a finished transcript goes besides the frontend event.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum OutputRoute {
+    /// Insert into the focused app using the delivery `mode`
+    Paste,
+    /// Put on the clipboard without pasting
+    ClipboardOnly,
+    /// Append to the Markdown journal at `journal_path`
+    AppendToFile,
+    /// Only emit the event for the frontend
+    EventOnly,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeliveryRoutes {
+    pub raw: OutputRoute,
+    pub refined: OutputRoute,
+}
+
+impl Default for DeliveryRoutes {
+    fn default() -> Self {
+        Self {
+            raw: OutputRoute::EventOnly,
+            refined: OutputRoute::Paste,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeliveryPreferences {
+    pub mode: DeliveryMode,
+    pub routes: DeliveryRoutes,
+    /// Journal file for `AppendToFile`, e.g. `~/notes/journal.md`, or a
+    /// daily pattern like `notes/YYYY-MM-DD.md`. Relative paths are taken
+    /// from the documents folder. Saved as `appendFilePath` before it was
+    /// renamed.
+    #[serde(alias = "appendFilePath")]
+    pub journal_path: Option<String>,
+    /// Typing speed in `Type` mode, some apps drop characters when it's too fast
+    pub typing_chars_per_second: u32,
+    /// How long to wait after pasting before restoring the old clipboard
+    pub clipboard_restore_delay_ms: u32,
+    /// Skip the restore and leave the transcript on the clipboard
+    pub leave_on_clipboard: bool,
+}
+
+impl Default for DeliveryPreferences {
+    fn default() -> Self {
+        Self {
+            mode: DeliveryMode::Paste,
+            routes: DeliveryRoutes::default(),
+            journal_path: None,
+            typing_chars_per_second: 60,
+            clipboard_restore_delay_ms: 500,
+            leave_on_clipboard: false,
+        }
+    }
+}
+
+impl Preferences {
+    /// Loads preferences from `path`, falling back to defaults if the file is
+    /// missing or unreadable.
+    pub fn load(path: &Path) -> Self {
+        match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                log::warn!("Failed to parse preferences, using defaults: {}", e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create config dir: {}", e))?;
+        }
+        let contents = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, contents).map_err(|e| format!("Failed to write preferences: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn journal_path_reads_the_old_key() {
+        let delivery: DeliveryPreferences = serde_json::from_str(r#"{"appendFilePath": "notes.md"}"#).unwrap();
+        assert_eq!(delivery.journal_path.as_deref(), Some("notes.md"));
+    }
+}
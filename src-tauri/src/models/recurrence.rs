@@ -0,0 +1,250 @@
+use chrono::{NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum Day {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+// RRULE's two-letter day codes, in `Day` order
+const DAY_CODES: [(&str, Day); 7] = [
+    ("MO", Day::Monday),
+    ("TU", Day::Tuesday),
+    ("WE", Day::Wednesday),
+    ("TH", Day::Thursday),
+    ("FR", Day::Friday),
+    ("SA", Day::Saturday),
+    ("SU", Day::Sunday),
+];
+
+impl Day {
+    pub fn weekday(self) -> Weekday {
+        match self {
+            Self::Monday => Weekday::Mon,
+            Self::Tuesday => Weekday::Tue,
+            Self::Wednesday => Weekday::Wed,
+            Self::Thursday => Weekday::Thu,
+            Self::Friday => Weekday::Fri,
+            Self::Saturday => Weekday::Sat,
+            Self::Sunday => Weekday::Sun,
+        }
+    }
+
+    fn code(self) -> &'static str {
+        DAY_CODES.iter().find(|(_, day)| *day == self).map_or("MO", |(code, _)| code)
+    }
+
+    fn from_code(code: &str) -> Option<Self> {
+        DAY_CODES.iter().find(|(c, _)| *c == code).map(|(_, day)| *day)
+    }
+}
+
+/// How a task repeats. Covers the daily, weekly and monthly parts of
+/// iCalendar's RRULE, and is stored as one.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    /// Every `interval` days, weeks or months
+    #[serde(default = "default_interval")]
+    pub interval: u32,
+    /// Only on these days of the week. A weekly rule without any repeats on
+    /// the day the task is assigned to.
+    #[serde(default)]
+    pub days: Vec<Day>,
+    /// Monthly only: the day of the month, the task's own day when unset. In
+    /// shorter months the last day is used instead.
+    #[serde(default)]
+    #[ts(optional)]
+    pub month_day: Option<u32>,
+    /// No occurrences after this day
+    #[serde(default)]
+    #[ts(optional)]
+    pub until: Option<NaiveDate>,
+    /// No more than this many occurrences
+    #[serde(default)]
+    #[ts(optional)]
+    pub count: Option<u32>,
+}
+
+fn default_interval() -> u32 {
+    1
+}
+
+impl Recurrence {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval == 0 {
+            return Err("Interval must be at least 1".to_string());
+        }
+        if self.month_day.is_some_and(|day| !(1..=31).contains(&day)) {
+            return Err("Day of the month must be between 1 and 31".to_string());
+        }
+        if self.month_day.is_some() && self.frequency != Frequency::Monthly {
+            return Err("Only monthly rules can set a day of the month".to_string());
+        }
+        if self.count == Some(0) {
+            return Err("Count must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// Formats the rule as an RRULE value, e.g. `FREQ=WEEKLY;BYDAY=MO,WE`.
+    pub fn to_rrule(&self) -> String {
+        let frequency = match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+        };
+
+        let mut parts = vec![format!("FREQ={}", frequency)];
+        if self.interval != 1 {
+            parts.push(format!("INTERVAL={}", self.interval));
+        }
+        if !self.days.is_empty() {
+            let days: Vec<&str> = self.days.iter().map(|day| day.code()).collect();
+            parts.push(format!("BYDAY={}", days.join(",")));
+        }
+        if let Some(day) = self.month_day {
+            parts.push(format!("BYMONTHDAY={}", day));
+        }
+        if let Some(until) = self.until {
+            parts.push(format!("UNTIL={}", until.format("%Y%m%d")));
+        }
+        if let Some(count) = self.count {
+            parts.push(format!("COUNT={}", count));
+        }
+        parts.join(";")
+    }
+
+    /// Parses an RRULE value. Only FREQ of DAILY, WEEKLY or MONTHLY with
+    /// INTERVAL, plain BYDAY codes, a single BYMONTHDAY, UNTIL and COUNT are
+    /// supported, anything else is rejected rather than half understood.
+    pub fn from_rrule(rule: &str) -> Result<Self, String> {
+        let rule = rule.trim();
+        let rule = rule.strip_prefix("RRULE:").unwrap_or(rule);
+
+        let mut frequency = None;
+        let mut recurrence = Self {
+            frequency: Frequency::Daily,
+            interval: 1,
+            days: Vec::new(),
+            month_day: None,
+            until: None,
+            count: None,
+        };
+
+        for part in rule.split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Malformed RRULE part: {}", part))?;
+            let invalid = || format!("Invalid {} in RRULE: {}", key, value);
+
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        _ => return Err(format!("Unsupported frequency: {}", value)),
+                    })
+                }
+                "INTERVAL" => recurrence.interval = value.parse().map_err(|_| invalid())?,
+                "BYDAY" => {
+                    recurrence.days = value
+                        .split(',')
+                        .map(|code| Day::from_code(&code.to_ascii_uppercase()).ok_or_else(invalid))
+                        .collect::<Result<_, _>>()?
+                }
+                "BYMONTHDAY" => recurrence.month_day = Some(value.parse().map_err(|_| invalid())?),
+                "UNTIL" => {
+                    // Dates and UTC datetimes, only the date part matters here
+                    let date = value.get(..8).ok_or_else(invalid)?;
+                    recurrence.until = Some(NaiveDate::parse_from_str(date, "%Y%m%d").map_err(|_| invalid())?);
+                }
+                "COUNT" => recurrence.count = Some(value.parse().map_err(|_| invalid())?),
+                _ => return Err(format!("Unsupported RRULE part: {}", key)),
+            }
+        }
+
+        recurrence.frequency = frequency.ok_or("RRULE has no FREQ")?;
+        recurrence.validate()?;
+        Ok(recurrence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rrule_round_trip() {
+        let rules = [
+            Recurrence {
+                frequency: Frequency::Weekly,
+                interval: 2,
+                days: vec![Day::Monday, Day::Friday],
+                month_day: None,
+                until: NaiveDate::from_ymd_opt(2026, 12, 31),
+                count: None,
+            },
+            Recurrence {
+                frequency: Frequency::Monthly,
+                interval: 1,
+                days: Vec::new(),
+                month_day: Some(31),
+                until: None,
+                count: Some(6),
+            },
+        ];
+
+        let formatted = rules.clone().map(|rule| rule.to_rrule());
+        assert_eq!(
+            formatted,
+            ["FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR;UNTIL=20261231", "FREQ=MONTHLY;BYMONTHDAY=31;COUNT=6"]
+        );
+        for (rule, formatted) in rules.iter().zip(&formatted) {
+            assert_eq!(&Recurrence::from_rrule(formatted).unwrap(), rule);
+        }
+    }
+
+    #[test]
+    fn reads_prefixed_rules_and_until_times() {
+        let rule = Recurrence::from_rrule("RRULE:FREQ=WEEKLY;BYDAY=MO,FR;UNTIL=20261231T235959Z").unwrap();
+
+        assert_eq!(rule.days, [Day::Monday, Day::Friday]);
+        assert_eq!(rule.until, NaiveDate::from_ymd_opt(2026, 12, 31));
+    }
+
+    #[test]
+    fn rejects_what_it_does_not_support() {
+        for rule in [
+            "FREQ=YEARLY",
+            "FREQ=WEEKLY;BYDAY=1MO",
+            "FREQ=MONTHLY;BYSETPOS=1",
+            "FREQ=WEEKLY;BYMONTHDAY=3",
+            "FREQ=DAILY;INTERVAL=0",
+            "INTERVAL=2",
+        ] {
+            assert!(Recurrence::from_rrule(rule).is_err(), "{} was accepted", rule);
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use crate::models::Recurrence;
+use chrono::{NaiveDate, NaiveTime};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+use ts_rs::TS;
+
+/// Identifies a task. The local store gives every task its own ID, which stays
+/// the same when a task created offline reaches the backend. The worker's IDs
+/// use this type too, they're only ever compared and put in URLs so they stay
+/// the strings it sends.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, TS)]
+#[serde(transparent)]
+#[ts(export)]
+pub struct TaskId(String);
+
+impl TaskId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for TaskId {
+    fn from(id: String) -> Self {
+        Self(id)
+    }
+}
+
+impl From<&str> for TaskId {
+    fn from(id: &str) -> Self {
+        Self(id.to_string())
+    }
+}
+
+impl fmt::Display for TaskId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+}
+
+impl Priority {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "low" => Some(Self::Low),
+            "medium" | "normal" => Some(Self::Medium),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+}
+
+/// A task as the app and frontend see it. Every task command takes and
+/// returns this, `api::TaskRecord` is the worker's JSON for it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct Task {
+    pub id: TaskId,
+    pub content: String,
+    pub completed: bool,
+    /// ISO 8601 date or datetime of the day the task belongs to. Older tasks
+    /// were created without one.
+    pub assigned_date: Option<String>,
+    /// Priority and tags aren't stored by the worker yet, they only live in
+    /// the local store
+    pub priority: Option<Priority>,
+    pub tags: Vec<String>,
+    /// Repeats starting from `assigned_date`. Kept in the local store only,
+    /// like priority and tags.
+    pub recurrence: Option<Recurrence>,
+    /// Local time of day on `assigned_date` the task is due, a reminder is
+    /// shown then. Local only too.
+    pub due_time: Option<NaiveTime>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+/// What the frontend sends to create a task
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct NewTask {
+    pub content: String,
+    pub assigned_date: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub priority: Option<Priority>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub recurrence: Option<Recurrence>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub due_time: Option<NaiveTime>,
+}
+
+/// A partial update, fields left out are kept as they are
+#[derive(Debug, Serialize, Deserialize, Clone, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export)]
+pub struct TaskChanges {
+    #[ts(optional)]
+    pub content: Option<String>,
+    #[ts(optional)]
+    pub completed: Option<bool>,
+    #[ts(optional)]
+    pub assigned_date: Option<String>,
+    #[ts(optional)]
+    pub priority: Option<Priority>,
+    #[ts(optional)]
+    pub tags: Option<Vec<String>>,
+    /// `null` stops the task repeating
+    #[serde(deserialize_with = "present")]
+    #[ts(optional, type = "Recurrence | null")]
+    pub recurrence: Option<Option<Recurrence>>,
+    /// `null` removes the due time
+    #[serde(deserialize_with = "present")]
+    #[ts(optional, type = "string | null")]
+    pub due_time: Option<Option<NaiveTime>>,
+}
+
+// Tells a field sent as `null` apart from one left out, which `default`
+// leaves as `None`
+fn present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+/// One day's instance of a task in the week view. A task that doesn't repeat
+/// has a single occurrence on its assigned day.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct TaskOccurrence {
+    pub task: Task,
+    pub date: NaiveDate,
+    /// Completing one occurrence of a repeating task leaves the others as
+    /// they are
+    pub completed: bool,
+}
+
+/// Sent as `task-reminder` when an occurrence of a task comes due, so the
+/// window can offer to snooze or complete it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct TaskReminder {
+    pub task: Task,
+    pub date: NaiveDate,
+    /// Due on an earlier day, typically missed while the app wasn't running
+    pub overdue: bool,
+}
+
+/// One task found in a transcript by `parse_tasks`, not saved until the user
+/// confirms it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct TaskDraft {
+    pub content: String,
+    /// `YYYY-MM-DD`, when the transcript named a day
+    pub assigned_date: Option<String>,
+    pub priority: Option<Priority>,
+    pub tags: Vec<String>,
+}
+
+/// How a transcript was split into drafts.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum TaskParser {
+    /// The built-in rules, always available
+    Local,
+    /// The backend's language model, through the refinement endpoint
+    Llm,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct TaskPreview {
+    pub drafts: Vec<TaskDraft>,
+    pub parser: TaskParser,
+}
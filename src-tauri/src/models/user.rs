@@ -21,22 +21,3 @@ pub struct ExistingUser {
     #[serde(rename = "updatedAt")]
     pub updated_at: Option<String>,
 }
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct RefinedText {
-    pub refined_text: String,
-    pub explanation: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Task {
-    pub id: i32,
-    pub name: String,
-    pub done: bool,
-    #[serde(rename = "userId")]
-    pub user_id: String,
-    #[serde(rename = "createdAt")]
-    pub created_at: String,
-    #[serde(rename = "updatedAt")]
-    pub updated_at: String,
-}
@@ -0,0 +1,188 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+use uuid::Uuid;
+
+const DEFAULT_PROFILE_ID: &str = "default";
+
+/// A separate account on the same machine, e.g. "Personal" and "Work".
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// All profiles and which one is active, kept in `profiles.json` in the app
+/// config dir.
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ProfileRegistry {
+    pub active: String,
+    pub profiles: Vec<Profile>,
+}
+
+impl Default for ProfileRegistry {
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_PROFILE_ID.to_string(),
+            profiles: vec![Profile {
+                id: DEFAULT_PROFILE_ID.to_string(),
+                name: "Default".to_string(),
+                created_at: Utc::now(),
+            }],
+        }
+    }
+}
+
+impl ProfileRegistry {
+    /// Loads the registry, creating one with a single default profile on
+    /// first run.
+    pub fn load(path: &Path) -> Self {
+        let registry = fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| {
+                serde_json::from_str::<Self>(&contents)
+                    .map_err(|e| log::warn!("Failed to parse profiles, using defaults: {}", e))
+                    .ok()
+            })
+            .filter(|registry| registry.get(&registry.active).is_some());
+
+        registry.unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create config dir: {}", e))?;
+        }
+        let contents = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, contents).map_err(|e| format!("Failed to write profiles: {}", e))
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|profile| profile.id == id)
+    }
+
+    /// `None` only for a registry edited since it was loaded, `load` makes
+    /// sure the active profile exists.
+    pub fn active_profile(&self) -> Option<&Profile> {
+        self.get(&self.active)
+    }
+
+    /// Adds a profile named `name` under a new random ID, so any name works
+    /// as long as no other profile has it.
+    pub fn add(&mut self, name: &str) -> Result<Profile, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Profile name can't be empty".to_string());
+        }
+        if self.profiles.iter().any(|profile| profile.name.to_lowercase() == name.to_lowercase()) {
+            return Err(format!("A profile named {} already exists", name));
+        }
+
+        let profile = Profile {
+            id: Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            created_at: Utc::now(),
+        };
+        self.profiles.push(profile.clone());
+        Ok(profile)
+    }
+
+    pub fn remove(&mut self, id: &str) -> Result<Profile, String> {
+        if id == self.active {
+            return Err("Can't delete the active profile".to_string());
+        }
+        let index = self
+            .profiles
+            .iter()
+            .position(|profile| profile.id == id)
+            .ok_or_else(|| format!("No profile with ID {}", id))?;
+        Ok(self.profiles.remove(index))
+    }
+}
+
+/// Where a profile keeps its files. Settings live under the app config dir,
+/// caches and queued recordings under the app data dir, each in a
+/// `profiles/<id>` subfolder so accounts never see each other's data.
+#[derive(Debug, Clone)]
+pub struct ProfilePaths {
+    pub id: String,
+    pub config_dir: PathBuf,
+    pub data_dir: PathBuf,
+    /// Where the default profile's files were kept before there were
+    /// profiles, `None` for every other profile
+    pub legacy_dir: Option<PathBuf>,
+}
+
+impl ProfilePaths {
+    pub fn new(app_config_dir: &Path, app_data_dir: &Path, id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            config_dir: app_config_dir.join("profiles").join(id),
+            data_dir: app_data_dir.join("profiles").join(id),
+            legacy_dir: (id == DEFAULT_PROFILE_ID).then(|| app_config_dir.to_path_buf()),
+        }
+    }
+
+    pub fn preferences(&self) -> PathBuf {
+        self.config_dir.join("preferences.json")
+    }
+
+    pub fn cache_dir(&self) -> PathBuf {
+        self.data_dir.join("cache")
+    }
+
+    pub fn recordings_dir(&self) -> PathBuf {
+        self.data_dir.join("recordings")
+    }
+
+    /// SQLite database holding the profile's tasks
+    pub fn task_db(&self) -> PathBuf {
+        self.data_dir.join("tasks.sqlite3")
+    }
+
+    /// Deletes cached data and any recordings that are still queued. Settings
+    /// are kept.
+    pub fn wipe_caches(&self) -> std::io::Result<()> {
+        for dir in [self.cache_dir(), self.recordings_dir()] {
+            if dir.exists() {
+                fs::remove_dir_all(&dir)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Deletes everything the profile stored on disk.
+    pub fn remove_all(&self) -> std::io::Result<()> {
+        for dir in [&self.config_dir, &self.data_dir] {
+            if dir.exists() {
+                fs::remove_dir_all(dir)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn any_name_gets_a_profile() {
+        let mut registry = ProfileRegistry::default();
+        let work = registry.add(" Работа ").unwrap();
+        assert_eq!(work.name, "Работа");
+        assert_ne!(work.id, DEFAULT_PROFILE_ID);
+
+        assert!(registry.add("работа").is_err());
+        assert!(registry.add("default").is_err());
+        assert!(registry.add("  ").is_err());
+        assert_eq!(registry.profiles.len(), 2);
+    }
+}
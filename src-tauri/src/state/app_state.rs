@@ -1,12 +1,35 @@
-use crate::models::{ExistingUser, User};
-use hound::WavWriter;
+use crate::api::JeffApiClient;
+use crate::audio::{PreRoll, RecorderMessage};
+use crate::auth::{SecretStore, SessionStore};
+use crate::error::AppError;
+use crate::models::{BackendConfig, ExistingUser, Preferences, User};
+use crate::profile::ProfilePaths;
+use crate::tasks::TaskStore;
+use crate::AppHandle;
 use serde::{Deserialize, Serialize};
-use std::fs::File;
-use std::io::BufWriter;
 use std::sync::atomic::AtomicBool;
 use std::sync::mpsc::Sender;
 use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
 use tempfile::NamedTempFile;
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+// Name of the signed-in user's entry in the secret store
+pub const USER_SECRET: &str = "user";
+
+/// The signed-in user as persisted between runs, alongside the session.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct StoredUser {
+    pub user: Option<User>,
+    pub existing_user: Option<ExistingUser>,
+}
+
+impl StoredUser {
+    pub fn load(secrets: &SecretStore) -> Self {
+        secrets.load(USER_SECRET).unwrap_or_default()
+    }
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum RecordingState {
@@ -18,11 +41,28 @@ pub enum RecordingState {
 pub struct AppState {
     pub user: Mutex<Option<User>>,
     pub existing_user: Mutex<Option<ExistingUser>>,
+    pub session: SessionStore,
+    pub secrets: Mutex<Arc<SecretStore>>,
+    /// Where the active profile keeps its settings, caches and recordings
+    pub profile: Mutex<ProfilePaths>,
+    /// Cancelled on logout and profile switch to abort in-flight requests
+    pub work: Mutex<CancellationToken>,
+    /// The active profile's tasks, swapped along with the profile
+    pub tasks: Mutex<Arc<TaskStore>>,
+    /// Wakes the task sync loop
+    pub sync_requested: Notify,
+    /// Wakes the reminder loop to work out its schedule again
+    pub reminders_changed: Notify,
+    pub preferences: Mutex<Preferences>,
+    pub backend: Mutex<BackendConfig>,
+    /// Shared by every backend request so connections are pooled
+    pub http_client: reqwest::Client,
     pub recording_state: Mutex<RecordingState>,
     pub is_recording: Arc<AtomicBool>,
-    pub audio_writer: Mutex<Option<Arc<Mutex<Option<(WavWriter<BufWriter<File>>, String)>>>>>,
-    pub recording_sender: Arc<Mutex<Option<Sender<()>>>>,
-    pub app_handle: tauri::AppHandle,
+    pub recording_sender: Arc<Mutex<Option<Sender<RecorderMessage>>>>,
+    pub recording_thread: Mutex<Option<JoinHandle<()>>>,
+    pub pre_roll: Mutex<Option<PreRoll>>,
+    pub app_handle: AppHandle,
     pub temp_file: Arc<Mutex<Option<NamedTempFile>>>,
     pub original_volume: Arc<Mutex<Option<f32>>>,
     #[cfg(target_os = "macos")]
@@ -30,3 +70,68 @@ pub struct AppState {
     #[cfg(not(target_os = "macos"))]
     pub audio_device_id: Arc<Mutex<Option<u32>>>,
 }
+
+impl AppState {
+    /// API client for the configured backend, authenticated with the current
+    /// session's access token (refreshed first if it's about to expire).
+    pub async fn api(&self) -> Result<JeffApiClient, AppError> {
+        let token = self.session.access_token(&self.http_client).await?;
+        let base_url = self.backend.lock()?.base_url.clone();
+        let cancellation = self.work.lock()?.clone();
+        Ok(JeffApiClient::with_http_client(self.http_client.clone(), base_url)
+            .with_token(token)
+            .with_cancellation(cancellation))
+    }
+
+    /// Backend ID of the signed-in user.
+    pub fn current_user_id(&self) -> Result<String, AppError> {
+        let user_guard = self.existing_user.lock()?;
+        user_guard.as_ref().map(|u| u.id.clone()).ok_or(AppError::Unauthenticated)
+    }
+
+    /// Saves `user` and `existing_user` so they're restored on the next run.
+    pub fn persist_user(&self) -> Result<(), AppError> {
+        let stored = StoredUser {
+            user: self.user.lock()?.clone(),
+            existing_user: self.existing_user.lock()?.clone(),
+        };
+        self.secrets.lock()?.save(USER_SECRET, &stored).map_err(AppError::Internal)
+    }
+
+    /// Forgets the signed-in user, in memory and on disk.
+    pub async fn clear_user(&self) -> Result<(), AppError> {
+        self.session.clear().await;
+        self.user.lock()?.take();
+        self.existing_user.lock()?.take();
+        self.secrets.lock()?.delete(USER_SECRET);
+        Ok(())
+    }
+
+    /// Aborts in-flight backend requests. Later requests get a fresh token.
+    pub fn cancel_work(&self) -> Result<(), AppError> {
+        let mut work = self.work.lock()?;
+        work.cancel();
+        *work = CancellationToken::new();
+        Ok(())
+    }
+
+    pub fn profile_paths(&self) -> Result<ProfilePaths, AppError> {
+        Ok(self.profile.lock()?.clone())
+    }
+
+    pub fn task_store(&self) -> Result<Arc<TaskStore>, AppError> {
+        Ok(Arc::clone(&*self.tasks.lock()?))
+    }
+
+    /// Asks the sync loop to push and pull tasks now rather than at its next
+    /// interval.
+    pub fn request_sync(&self) {
+        self.sync_requested.notify_one();
+    }
+
+    /// Tells the reminder loop that tasks changed, so a new or moved due time
+    /// isn't missed while it sleeps.
+    pub fn reschedule_reminders(&self) {
+        self.reminders_changed.notify_one();
+    }
+}
@@ -2,3 +2,4 @@ mod app_state;
 
 pub use app_state::AppState;
 pub use app_state::RecordingState;
+pub use app_state::StoredUser;
@@ -0,0 +1,11 @@
+mod occurrences;
+mod parse;
+mod reminders;
+mod store;
+mod sync;
+
+pub use occurrences::expand_occurrences;
+pub use parse::{llm_instructions, parse_llm_reply, parse_transcript};
+pub use reminders::spawn_reminder_loop;
+pub use store::TaskStore;
+pub use sync::{emit_tasks_changed, spawn_sync_loop};
@@ -0,0 +1,183 @@
+use crate::models::{Frequency, Recurrence, Task, TaskId, TaskOccurrence};
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate};
+use std::collections::HashSet;
+
+/// Lays tasks out over the days between `from` and `to` inclusive. A task
+/// without a rule shows up once, on its own day, and a repeating one on every
+/// day its rule hits. Occurrences of a repeating task are completed one by
+/// one, those listed in `completions`.
+pub fn expand_occurrences(
+    tasks: Vec<Task>,
+    completions: &HashSet<(TaskId, NaiveDate)>,
+    from: NaiveDate,
+    to: NaiveDate,
+    today: NaiveDate,
+) -> Vec<TaskOccurrence> {
+    let mut expanded = Vec::new();
+
+    for task in tasks {
+        let anchor = task.assigned_date.as_deref().and_then(task_day).unwrap_or(today);
+
+        match &task.recurrence {
+            None => {
+                if (from..=to).contains(&anchor) {
+                    expanded.push(TaskOccurrence {
+                        completed: task.completed,
+                        date: anchor,
+                        task,
+                    });
+                }
+            }
+            Some(rule) => {
+                for date in occurrences(rule, anchor, from, to) {
+                    expanded.push(TaskOccurrence {
+                        completed: completions.contains(&(task.id.clone(), date)),
+                        date,
+                        task: task.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    // Stable, so tasks on the same day keep the store's oldest-first order
+    expanded.sort_by_key(|occurrence| occurrence.date);
+    expanded
+}
+
+/// The days between `from` and `to` inclusive on which a rule starting on
+/// `anchor` repeats. The anchor is always the first occurrence.
+pub fn occurrences(rule: &Recurrence, anchor: NaiveDate, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+    let end = rule.until.map_or(to, |until| until.min(to));
+    // With a count every occurrence since the anchor has to be seen to know
+    // when it runs out
+    let mut day = if rule.count.is_some() { anchor } else { anchor.max(from) };
+
+    let mut dates = Vec::new();
+    let mut seen = 0;
+    while day <= end {
+        if matches(rule, anchor, day) {
+            seen += 1;
+            if rule.count.is_some_and(|count| seen > count) {
+                break;
+            }
+            if day >= from {
+                dates.push(day);
+            }
+        }
+        match day.checked_add_days(Days::new(1)) {
+            Some(next) => day = next,
+            None => break,
+        }
+    }
+    dates
+}
+
+fn matches(rule: &Recurrence, anchor: NaiveDate, day: NaiveDate) -> bool {
+    if day == anchor {
+        return true;
+    }
+
+    let interval = i64::from(rule.interval.max(1));
+    let on_days = |day: NaiveDate| rule.days.iter().any(|d| d.weekday() == day.weekday());
+
+    match rule.frequency {
+        Frequency::Daily => {
+            (day - anchor).num_days() % interval == 0 && (rule.days.is_empty() || on_days(day))
+        }
+        Frequency::Weekly => {
+            let weeks = (week_start(day) - week_start(anchor)).num_weeks();
+            let on_day = if rule.days.is_empty() {
+                day.weekday() == anchor.weekday()
+            } else {
+                on_days(day)
+            };
+            weeks % interval == 0 && on_day
+        }
+        Frequency::Monthly => {
+            let months = i64::from(day.year() * 12 + day.month0() as i32)
+                - i64::from(anchor.year() * 12 + anchor.month0() as i32);
+            if months % interval != 0 {
+                return false;
+            }
+            // Without a day of the month, BYDAY means every one of those
+            // weekdays in the month
+            if rule.month_day.is_none() && !rule.days.is_empty() {
+                return on_days(day);
+            }
+            let target = rule.month_day.unwrap_or(anchor.day()).min(days_in_month(day));
+            day.day() == target && (rule.days.is_empty() || on_days(day))
+        }
+    }
+}
+
+fn week_start(day: NaiveDate) -> NaiveDate {
+    day - Days::new(u64::from(day.weekday().num_days_from_monday()))
+}
+
+fn days_in_month(day: NaiveDate) -> u32 {
+    let (year, month) = if day.month() == 12 { (day.year() + 1, 1) } else { (day.year(), day.month() + 1) };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|first| first.pred_opt())
+        .map_or(31, |last| last.day())
+}
+
+/// The local day a task is assigned to. The frontend sends RFC3339
+/// timestamps, drafts from dictation plain dates.
+pub fn task_day(assigned_date: &str) -> Option<NaiveDate> {
+    assigned_date.parse::<NaiveDate>().ok().or_else(|| {
+        DateTime::parse_from_rfc3339(assigned_date)
+            .ok()
+            .map(|date| date.with_timezone(&Local).date_naive())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(date: &str) -> NaiveDate {
+        date.parse().unwrap()
+    }
+
+    fn dates(rule: &str, anchor: &str, from: &str, to: &str) -> Vec<NaiveDate> {
+        let rule = Recurrence::from_rrule(rule).unwrap();
+        occurrences(&rule, day(anchor), day(from), day(to))
+    }
+
+    #[test]
+    fn weekdays() {
+        // A Monday to Sunday week
+        let dates = dates("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR", "2026-10-19", "2026-10-19", "2026-10-25");
+
+        assert_eq!(dates, ["2026-10-19", "2026-10-20", "2026-10-21", "2026-10-22", "2026-10-23"].map(day));
+    }
+
+    #[test]
+    fn every_other_week() {
+        let dates = dates("FREQ=WEEKLY;INTERVAL=2", "2026-10-21", "2026-10-19", "2026-11-20");
+
+        assert_eq!(dates, ["2026-10-21", "2026-11-04", "2026-11-18"].map(day));
+    }
+
+    #[test]
+    fn monthly_on_the_31st_falls_back_to_the_last_day() {
+        let dates = dates("FREQ=MONTHLY", "2027-01-31", "2027-01-01", "2027-04-30");
+
+        assert_eq!(dates, ["2027-01-31", "2027-02-28", "2027-03-31", "2027-04-30"].map(day));
+    }
+
+    #[test]
+    fn count_includes_occurrences_before_the_range() {
+        let dates = dates("FREQ=DAILY;COUNT=5", "2026-10-19", "2026-10-22", "2026-10-31");
+
+        assert_eq!(dates, ["2026-10-22", "2026-10-23"].map(day));
+    }
+
+    #[test]
+    fn nothing_after_until() {
+        let dates = dates("FREQ=DAILY;UNTIL=20261021", "2026-10-19", "2026-10-19", "2026-10-31");
+
+        assert_eq!(dates, ["2026-10-19", "2026-10-20", "2026-10-21"].map(day));
+    }
+}
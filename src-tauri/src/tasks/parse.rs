@@ -0,0 +1,612 @@
+//! Splits a dictated transcript into task drafts. The local parser is a set of
+//! fixed rules, so the same transcript on the same day always gives the same
+//! drafts. The LLM path only builds the instructions for the refinement
+//! endpoint and checks its reply, the caller sends the request.
+
+use crate::models::{Priority, TaskDraft};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::Deserialize;
+
+// Lead-ins dictation tends to start a task with, checked longest first
+const FILLERS: &[&str] = &[
+    "don't forget to",
+    "remind me to",
+    "i need to",
+    "i have to",
+    "i've got to",
+    "i got to",
+    "i want to",
+    "i'd like to",
+    "make sure to",
+    "remember to",
+    "i gotta",
+    "i should",
+    "i must",
+    "need to",
+    "have to",
+    "please",
+    "and",
+    "so",
+    "okay",
+    "ok",
+    "um",
+    "uh",
+];
+
+// Words that can introduce a date, dropped along with it
+const DATE_PREPOSITIONS: &[&str] = &["on", "by", "due", "for", "before", "until"];
+
+const MONTHS: &[(&str, u32)] = &[
+    ("january", 1),
+    ("jan", 1),
+    ("february", 2),
+    ("feb", 2),
+    ("march", 3),
+    ("mar", 3),
+    ("april", 4),
+    ("apr", 4),
+    ("may", 5),
+    ("june", 6),
+    ("jun", 6),
+    ("july", 7),
+    ("jul", 7),
+    ("august", 8),
+    ("aug", 8),
+    ("september", 9),
+    ("sept", 9),
+    ("sep", 9),
+    ("october", 10),
+    ("oct", 10),
+    ("november", 11),
+    ("nov", 11),
+    ("december", 12),
+    ("dec", 12),
+];
+
+struct Word<'a> {
+    text: &'a str,
+    /// Lowercased, with surrounding punctuation and curly quotes dealt with
+    norm: String,
+}
+
+impl<'a> Word<'a> {
+    fn new(text: &'a str) -> Self {
+        let norm = text
+            .replace('\u{2019}', "'")
+            .to_lowercase()
+            .trim_matches(|c: char| !c.is_alphanumeric() && c != '\'' && c != '#')
+            .to_string();
+        Self { text, norm }
+    }
+}
+
+/// Splits `transcript` into one draft per task it mentions. Relative dates
+/// are resolved against `today`, weeks start on Monday.
+pub fn parse_transcript(transcript: &str, today: NaiveDate) -> Vec<TaskDraft> {
+    split_items(transcript)
+        .iter()
+        .flat_map(|item| {
+            let words: Vec<Word> = item.split_whitespace().map(Word::new).collect();
+            split_on_and(&words, today)
+                .into_iter()
+                .filter_map(|words| parse_item(words, today))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+// Items are separated by line breaks, sentence ends and spoken connectors
+// like "and then" or "also"
+fn split_items(transcript: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    for line in transcript.lines() {
+        let mut current = String::new();
+        let mut chars = strip_list_marker(line.trim()).chars().peekable();
+        while let Some(c) = chars.next() {
+            let ends_sentence =
+                matches!(c, '.' | '!' | '?' | ';') && chars.peek().map_or(true, |next| next.is_whitespace());
+            if ends_sentence {
+                sentences.push(std::mem::take(&mut current));
+            } else {
+                current.push(c);
+            }
+        }
+        sentences.push(current);
+    }
+
+    let mut items = Vec::new();
+    for sentence in sentences {
+        let words: Vec<&str> = sentence.split_whitespace().collect();
+        let mut current: Vec<&str> = Vec::new();
+        let mut i = 0;
+        while i < words.len() {
+            let norm = Word::new(words[i]).norm;
+            let next = words.get(i + 1).map(|word| Word::new(word).norm);
+            let connector = match norm.as_str() {
+                "then" | "also" | "afterwards" => 1,
+                "after" if next.as_deref() == Some("that") => 2,
+                _ => 0,
+            };
+            if connector == 0 {
+                current.push(words[i]);
+                i += 1;
+                continue;
+            }
+
+            // "and then" splits as one connector
+            if current.last().is_some_and(|word| Word::new(word).norm == "and") {
+                current.pop();
+            }
+            items.push(current.join(" "));
+            current.clear();
+            i += connector;
+        }
+        items.push(current.join(" "));
+    }
+
+    items.retain(|item| !item.trim().is_empty());
+    items
+}
+
+// "and" only separates two tasks when a new clause starts after it, "and
+// remind me to call mom", or when both sides have a day of their own, "buy
+// milk tomorrow and call mom next Friday". "Buy bread and milk" stays one.
+fn split_on_and<'w, 'a>(words: &'w [Word<'a>], today: NaiveDate) -> Vec<&'w [Word<'a>]> {
+    let mut items = Vec::new();
+    let mut start = 0;
+    for i in 0..words.len() {
+        if i == start || words[i].norm != "and" {
+            continue;
+        }
+        let (before, after) = (&words[start..i], &words[i + 1..]);
+        if starts_clause(after) || (has_date(before, today) && has_date(after, today)) {
+            items.push(before);
+            start = i + 1;
+        }
+    }
+    items.push(&words[start..]);
+    items
+}
+
+fn starts_clause(words: &[Word]) -> bool {
+    FILLERS.iter().filter(|filler| filler.contains(' ')).any(|filler| {
+        let filler: Vec<&str> = filler.split(' ').collect();
+        words.len() > filler.len() && words.iter().zip(&filler).all(|(word, filler)| word.norm == *filler)
+    })
+}
+
+fn has_date(words: &[Word], today: NaiveDate) -> bool {
+    (0..words.len()).any(|i| dated_at(words, i, today).is_some())
+}
+
+fn strip_list_marker(line: &str) -> &str {
+    for marker in ["- ", "* ", "\u{2022} "] {
+        if let Some(rest) = line.strip_prefix(marker) {
+            return rest;
+        }
+    }
+
+    // "1. " and "1) "
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 {
+        let rest = &line[digits..];
+        if let Some(rest) = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") ")) {
+            return rest;
+        }
+    }
+    line
+}
+
+fn parse_item(words: &[Word], today: NaiveDate) -> Option<TaskDraft> {
+    let mut used = vec![false; words.len()];
+    let mut assigned_date = None;
+    let mut priority = None;
+    let mut tags: Vec<String> = Vec::new();
+
+    let mut i = 0;
+    while i < words.len() {
+        let found = if let Some((len, tag)) = tag_at(words, i) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+            Some(len)
+        } else if let Some((len, found)) = priority.is_none().then(|| priority_at(words, i)).flatten() {
+            priority = Some(found);
+            Some(len)
+        } else if let Some((len, date)) = assigned_date.is_none().then(|| dated_at(words, i, today)).flatten() {
+            assigned_date = Some(date);
+            Some(len)
+        } else {
+            None
+        };
+
+        match found {
+            Some(len) => {
+                used[i..i + len].iter_mut().for_each(|used| *used = true);
+                i += len;
+            }
+            None => i += 1,
+        }
+    }
+
+    let remaining: Vec<&Word> = words.iter().zip(&used).filter(|(_, used)| !**used).map(|(word, _)| word).collect();
+    let content = tidy(strip_fillers(&remaining));
+    if content.is_empty() {
+        return None;
+    }
+
+    Some(TaskDraft {
+        content,
+        assigned_date: assigned_date.map(|date| date.format("%Y-%m-%d").to_string()),
+        priority,
+        tags,
+    })
+}
+
+fn strip_fillers<'w>(mut words: &'w [&'w Word<'w>]) -> &'w [&'w Word<'w>] {
+    'outer: loop {
+        for filler in FILLERS {
+            let filler: Vec<&str> = filler.split(' ').collect();
+            let matches = words.len() >= filler.len()
+                && words.iter().zip(&filler).all(|(word, filler)| word.norm == *filler);
+            if matches {
+                words = &words[filler.len()..];
+                continue 'outer;
+            }
+        }
+        break;
+    }
+
+    // A connector left dangling by a removed date, e.g. "buy milk and tomorrow"
+    while words.last().is_some_and(|word| word.norm == "and") {
+        words = &words[..words.len() - 1];
+    }
+    words
+}
+
+// Joins what's left and cleans up the edges: no stray punctuation and a
+// capital first letter
+fn tidy(words: &[&Word]) -> String {
+    let joined = words.iter().map(|word| word.text).collect::<Vec<_>>().join(" ");
+    let trimmed = joined.trim_matches(|c: char| c.is_whitespace() || matches!(c, ',' | ':' | ';' | '-'));
+
+    let mut chars = trimmed.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn norm_at<'w>(words: &'w [Word], i: usize) -> &'w str {
+    words.get(i).map_or("", |word| word.norm.as_str())
+}
+
+// "#errands", "hashtag errands" and "tagged as errands"
+fn tag_at(words: &[Word], i: usize) -> Option<(usize, String)> {
+    let word = norm_at(words, i);
+    let (len, tag) = if let Some(tag) = word.strip_prefix('#') {
+        (1, tag)
+    } else if word == "hashtag" {
+        (2, norm_at(words, i + 1))
+    } else if matches!(word, "tag" | "tagged") && matches!(norm_at(words, i + 1), "as" | "with") {
+        (3, norm_at(words, i + 2))
+    } else {
+        return None;
+    };
+
+    let tag: String = tag
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, '-' | '_'))
+        .collect();
+    (!tag.is_empty()).then_some((len, tag))
+}
+
+fn priority_at(words: &[Word], i: usize) -> Option<(usize, Priority)> {
+    // "it's urgent", "it is urgent"
+    let lead = match (norm_at(words, i), norm_at(words, i + 1)) {
+        ("it's" | "its", _) => 1,
+        ("it", "is") => 2,
+        _ => 0,
+    };
+    if lead > 0 {
+        return priority_at(words, i + lead).map(|(len, priority)| (lead + len, priority));
+    }
+
+    let found = match (norm_at(words, i), norm_at(words, i + 1)) {
+        ("urgent" | "urgently" | "asap", _) => (1, Priority::High),
+        ("high" | "top", "priority") => (2, Priority::High),
+        ("medium" | "normal", "priority") => (2, Priority::Medium),
+        ("low", "priority") => (2, Priority::Low),
+        ("no", "rush") => (2, Priority::Low),
+        ("as", "soon") if norm_at(words, i + 2) == "as" && norm_at(words, i + 3) == "possible" => {
+            (4, Priority::High)
+        }
+        _ => return None,
+    };
+    Some(found)
+}
+
+// A date phrase at `i`, along with any preposition leading into it
+fn dated_at(words: &[Word], i: usize, today: NaiveDate) -> Option<(usize, NaiveDate)> {
+    let mut start = i;
+    while DATE_PREPOSITIONS.contains(&norm_at(words, start)) {
+        start += 1;
+    }
+    date_at(words, start, today).map(|(len, date)| (start - i + len, date))
+}
+
+fn date_at(words: &[Word], i: usize, today: NaiveDate) -> Option<(usize, NaiveDate)> {
+    let word = |offset: usize| norm_at(words, i + offset);
+
+    let found = match word(0) {
+        "today" | "tonight" => (1, today),
+        "tomorrow" | "tmrw" => (1, today + Duration::days(1)),
+        "day" if word(1) == "after" && word(2) == "tomorrow" => (3, today + Duration::days(2)),
+        "the" if word(1) == "day" && word(2) == "after" && word(3) == "tomorrow" => (4, today + Duration::days(2)),
+        "this" if word(1) == "weekend" => (2, weekend(today)),
+        "this" => (2, upcoming(today, weekday(word(1))?)),
+        "next" if word(1) == "week" => (2, next_week(today)),
+        "next" => {
+            let weekday = weekday(word(1))?;
+            (2, next_week(today) + Duration::days(weekday.num_days_from_monday() as i64))
+        }
+        "in" => {
+            let amount = amount(word(1))?;
+            let days = match word(2) {
+                "day" | "days" => amount,
+                "week" | "weeks" => amount * 7,
+                _ => return None,
+            };
+            (3, today + Duration::days(days))
+        }
+        "the" if word(2) == "of" => (4, month_day(today, word(3), word(1))?),
+        first => match weekday(first) {
+            Some(weekday) => (1, upcoming(today, weekday)),
+            None => month_day(today, first, word(1))
+                .or_else(|| month_day(today, word(1), first))
+                .map(|date| (2, date))?,
+        },
+    };
+    Some(found)
+}
+
+// "sat" and "sun" are left out, they're too often just words: "put on sun
+// cream" isn't due on Sunday
+fn weekday(word: &str) -> Option<Weekday> {
+    let weekday = match word {
+        "monday" | "mon" => Weekday::Mon,
+        "tuesday" | "tue" | "tues" => Weekday::Tue,
+        "wednesday" | "wed" => Weekday::Wed,
+        "thursday" | "thu" | "thurs" => Weekday::Thu,
+        "friday" | "fri" => Weekday::Fri,
+        "saturday" => Weekday::Sat,
+        "sunday" => Weekday::Sun,
+        _ => return None,
+    };
+    Some(weekday)
+}
+
+// The next `weekday`, today included
+fn upcoming(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let ahead = (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+    today + Duration::days(ahead as i64)
+}
+
+// Monday of next week
+fn next_week(today: NaiveDate) -> NaiveDate {
+    today + Duration::days(7 - today.weekday().num_days_from_monday() as i64)
+}
+
+fn weekend(today: NaiveDate) -> NaiveDate {
+    match today.weekday() {
+        Weekday::Sat | Weekday::Sun => today,
+        _ => upcoming(today, Weekday::Sat),
+    }
+}
+
+fn amount(word: &str) -> Option<i64> {
+    let amount = match word {
+        "a" | "an" | "one" => 1,
+        "two" => 2,
+        "three" => 3,
+        "four" => 4,
+        "five" => 5,
+        "six" => 6,
+        "seven" => 7,
+        "eight" => 8,
+        "nine" => 9,
+        "ten" => 10,
+        digits => digits.parse().ok().filter(|amount| (1..=365).contains(amount))?,
+    };
+    Some(amount)
+}
+
+// "october 20", "20th october". Dates already past this year are next year's.
+fn month_day(today: NaiveDate, month: &str, day: &str) -> Option<NaiveDate> {
+    let month = MONTHS.iter().find(|(name, _)| *name == month)?.1;
+    let day: u32 = day
+        .trim_end_matches(|c: char| c.is_ascii_alphabetic())
+        .parse()
+        .ok()?;
+
+    let date = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+    if date < today {
+        return NaiveDate::from_ymd_opt(today.year() + 1, month, day);
+    }
+    Some(date)
+}
+
+/// Instructions sent as the refinement's context to have the model split the
+/// transcript into tasks instead of rewriting it.
+pub fn llm_instructions(today: NaiveDate) -> String {
+    format!(
+        "Split this dictation into separate to-do items. Reply with only a JSON array and no other text. \
+         Each item is an object with \"content\" (the task, without its date, priority or tags), \
+         \"date\" (YYYY-MM-DD, or null when no day is mentioned; today is {} {}), \
+         \"priority\" (\"low\", \"medium\", \"high\" or null) and \"tags\" (an array of short lowercase words).",
+        today.format("%A"),
+        today.format("%Y-%m-%d"),
+    )
+}
+
+#[derive(Deserialize)]
+struct LlmTask {
+    content: String,
+    #[serde(default)]
+    date: Option<String>,
+    #[serde(default)]
+    priority: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Reads the drafts out of the model's reply. Fails when the reply isn't the
+/// JSON asked for, so the caller can fall back to the local parser. Dates and
+/// priorities that don't parse are dropped rather than failing the reply.
+pub fn parse_llm_reply(reply: &str) -> Result<Vec<TaskDraft>, String> {
+    // Models like to wrap JSON in a code fence or a sentence
+    let start = reply.find('[').ok_or("Reply has no JSON array")?;
+    let end = reply.rfind(']').ok_or("Reply has no JSON array")?;
+    if end < start {
+        return Err("Reply has no JSON array".to_string());
+    }
+
+    let tasks: Vec<LlmTask> = serde_json::from_str(&reply[start..=end]).map_err(|e| e.to_string())?;
+    let drafts: Vec<TaskDraft> = tasks
+        .into_iter()
+        .filter(|task| !task.content.trim().is_empty())
+        .map(|task| TaskDraft {
+            content: task.content.trim().to_string(),
+            assigned_date: task
+                .date
+                .filter(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok()),
+            priority: task.priority.as_deref().and_then(Priority::parse),
+            tags: task
+                .tags
+                .iter()
+                .map(|tag| tag.trim().trim_start_matches('#').to_lowercase())
+                .filter(|tag| !tag.is_empty())
+                .collect(),
+        })
+        .collect();
+
+    if drafts.is_empty() {
+        return Err("Reply has no tasks".to_string());
+    }
+    Ok(drafts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A Wednesday
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 21).unwrap()
+    }
+
+    // Content and day of each draft
+    fn parse(transcript: &str) -> Vec<(String, Option<String>)> {
+        parse_transcript(transcript, today())
+            .into_iter()
+            .map(|draft| (draft.content, draft.assigned_date))
+            .collect()
+    }
+
+    fn task(content: &str, date: Option<&str>) -> (String, Option<String>) {
+        (content.to_string(), date.map(str::to_string))
+    }
+
+    #[test]
+    fn relative_days() {
+        assert_eq!(parse("buy milk tomorrow"), [task("Buy milk", Some("2026-10-22"))]);
+        assert_eq!(parse("call the bank next Friday"), [task("Call the bank", Some("2026-10-30"))]);
+        assert_eq!(
+            parse("file taxes the day after tomorrow"),
+            [task("File taxes", Some("2026-10-23"))]
+        );
+        assert_eq!(parse("renew my passport in two weeks"), [task("Renew my passport", Some("2026-11-04"))]);
+    }
+
+    #[test]
+    fn dates_already_past_are_next_year() {
+        assert_eq!(parse("book flights on 20th october"), [task("Book flights", Some("2027-10-20"))]);
+        assert_eq!(parse("book flights on October 25"), [task("Book flights", Some("2026-10-25"))]);
+    }
+
+    #[test]
+    fn ordinary_words_are_not_days() {
+        assert_eq!(parse("put on sun cream"), [task("Put on sun cream", None)]);
+    }
+
+    #[test]
+    fn priorities() {
+        let priority = |transcript: &str| {
+            let draft = parse_transcript(transcript, today()).remove(0);
+            (draft.content, draft.priority)
+        };
+        assert_eq!(priority("fix the leak, it's urgent"), ("Fix the leak".to_string(), Some(Priority::High)));
+        assert_eq!(priority("water the plants low priority"), ("Water the plants".to_string(), Some(Priority::Low)));
+        assert_eq!(
+            priority("send the invoice as soon as possible"),
+            ("Send the invoice".to_string(), Some(Priority::High))
+        );
+    }
+
+    #[test]
+    fn tags() {
+        let drafts = parse_transcript("buy stamps #errands hashtag post", today());
+
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(drafts[0].content, "Buy stamps");
+        assert_eq!(drafts[0].tags, ["errands", "post"]);
+    }
+
+    #[test]
+    fn splits_on_connectors_and_list_markers() {
+        assert_eq!(parse("call the bank then email Sam"), [task("Call the bank", None), task("Email Sam", None)]);
+        assert_eq!(parse("- buy milk\n2. call mom"), [task("Buy milk", None), task("Call mom", None)]);
+    }
+
+    #[test]
+    fn splits_on_and_between_tasks_only() {
+        assert_eq!(
+            parse("buy milk tomorrow and call mom next Friday"),
+            [task("Buy milk", Some("2026-10-22")), task("Call mom", Some("2026-10-30"))]
+        );
+        assert_eq!(
+            parse("buy bread and remind me to water the plants"),
+            [task("Buy bread", None), task("Water the plants", None)]
+        );
+        assert_eq!(parse("buy bread and milk tomorrow"), [task("Buy bread and milk", Some("2026-10-22"))]);
+    }
+
+    #[test]
+    fn llm_replies_are_read_from_the_json_array() {
+        let reply = r#"Here you go:
+```json
+[
+    {"content": " Buy milk ", "date": "2026-10-22", "priority": "high", "tags": ["#Errands"]},
+    {"content": "Call mom", "date": "Friday"},
+    {"content": ""}
+]
+```"#;
+
+        let drafts = parse_llm_reply(reply).unwrap();
+
+        assert_eq!(drafts.len(), 2);
+        assert_eq!(drafts[0].content, "Buy milk");
+        assert_eq!(drafts[0].assigned_date.as_deref(), Some("2026-10-22"));
+        assert_eq!(drafts[0].priority, Some(Priority::High));
+        assert_eq!(drafts[0].tags, ["errands"]);
+        // A day the model didn't turn into a date is dropped, not the task
+        assert_eq!(drafts[1].assigned_date, None);
+    }
+
+    #[test]
+    fn llm_replies_without_tasks_fail() {
+        assert!(parse_llm_reply("Sorry, I can't help with that.").is_err());
+        assert!(parse_llm_reply("[]").is_err());
+        assert!(parse_llm_reply("] [").is_err());
+    }
+}
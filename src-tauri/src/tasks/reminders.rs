@@ -0,0 +1,252 @@
+use crate::error::AppError;
+use crate::models::{TaskId, TaskOccurrence, TaskReminder};
+use crate::state::AppState;
+use crate::tasks::occurrences::{expand_occurrences, task_day};
+use crate::tasks::store::{Reminder, TaskStore};
+use crate::AppHandle;
+use chrono::{DateTime, Days, Local, NaiveDate, TimeZone, Utc};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{Emitter, EventTarget, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+// Longest the loop goes without looking at the store, which bounds how late
+// a reminder is after the clock or time zone changes
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+// Occurrences missed further back than this aren't brought up again
+const OVERDUE_DAYS: u64 = 7;
+
+// More reminders than this coming due at once, typically at startup after a
+// few days away, are shown as a single notification
+const MAX_NOTIFICATIONS: usize = 3;
+
+/// Shows a notification when a task's due time comes, and sends it to the
+/// window as `task-reminder` so it can be snoozed or completed. The schedule
+/// is worked out from the store every time round, so nothing is lost across
+/// restarts and reminders missed while the app was closed are shown at
+/// startup as overdue.
+pub fn spawn_reminder_loop(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let state = app_handle.state::<AppState>();
+            let next = remind(&state).unwrap_or_else(|e| {
+                log::warn!("Task reminders failed: {}", e);
+                None
+            });
+
+            let sleep = next
+                .and_then(|next| (next - Utc::now()).to_std().ok())
+                .map_or(MAX_SLEEP, |until| until.min(MAX_SLEEP));
+            tokio::select! {
+                _ = state.reminders_changed.notified() => {}
+                _ = tokio::time::sleep(sleep) => {}
+            }
+        }
+    });
+}
+
+// Shows the reminders that have come due and returns when the next one will
+fn remind(state: &AppState) -> Result<Option<DateTime<Utc>>, AppError> {
+    let store = state.task_store()?;
+    let today = Local::now().date_naive();
+    let from = today - Days::new(OVERDUE_DAYS);
+
+    let completions = store.completions(from, today)?;
+    let occurrences = expand_occurrences(store.list()?, &completions, from, today, today);
+    let (due, next) = due_reminders(occurrences, &store.reminders(from, today)?, Utc::now(), today);
+
+    if !due.is_empty() {
+        show(state, &store, &due)?;
+    }
+    Ok(next)
+}
+
+// The reminders that have come due by `now` and when the next one will,
+// given the occurrences up to `today` and the reminders already shown or
+// snoozed
+fn due_reminders(
+    occurrences: Vec<TaskOccurrence>,
+    reminders: &HashMap<(TaskId, NaiveDate), Reminder>,
+    now: DateTime<Utc>,
+    today: NaiveDate,
+) -> (Vec<TaskReminder>, Option<DateTime<Utc>>) {
+    let mut due = Vec::new();
+    let mut next: Option<DateTime<Utc>> = None;
+
+    for occurrence in occurrences {
+        let Some(due_time) = occurrence.task.due_time else {
+            continue;
+        };
+        if occurrence.completed {
+            continue;
+        }
+        // Undated tasks are laid out on whatever day it is, they'd be due
+        // again every day
+        if occurrence.task.assigned_date.as_deref().and_then(task_day).is_none() {
+            continue;
+        }
+
+        let remind_at = match reminders.get(&(occurrence.task.id.clone(), occurrence.date)) {
+            Some(reminder) if reminder.shown => continue,
+            Some(reminder) => reminder.remind_at,
+            // Skipped when a DST change means the time never happens that day
+            None => match Local.from_local_datetime(&occurrence.date.and_time(due_time)).earliest() {
+                Some(due_at) => due_at.with_timezone(&Utc),
+                None => continue,
+            },
+        };
+
+        if remind_at <= now {
+            due.push(TaskReminder {
+                overdue: occurrence.date < today,
+                task: occurrence.task,
+                date: occurrence.date,
+            });
+        } else {
+            next = Some(next.map_or(remind_at, |next| next.min(remind_at)));
+        }
+    }
+    (due, next)
+}
+
+// Reminders are marked shown before they're sent, one whose notification
+// fails isn't retried every time round
+fn show(state: &AppState, store: &TaskStore, due: &[TaskReminder]) -> Result<(), AppError> {
+    for reminder in due {
+        store.mark_reminded(&reminder.task.id, reminder.date)?;
+    }
+    log::info!("Showing {} task reminders", due.len());
+
+    if due.len() > MAX_NOTIFICATIONS {
+        notify(state, &format!("{} tasks are due", due.len()), "Open the tasks page to catch up");
+    } else {
+        for reminder in due {
+            notify(state, &reminder.task.content, &describe(reminder));
+        }
+    }
+
+    for reminder in due {
+        state.app_handle.emit_to(EventTarget::any(), "task-reminder", reminder)?;
+    }
+    Ok(())
+}
+
+fn describe(reminder: &TaskReminder) -> String {
+    let time = reminder.task.due_time.map(|time| time.format("%H:%M").to_string()).unwrap_or_default();
+    if reminder.overdue {
+        format!("Was due {} at {}", reminder.date.format("%A"), time)
+    } else {
+        format!("Due at {}", time)
+    }
+}
+
+// Desktop notifications can't carry actions, snoozing and completing happen
+// in the window
+fn notify(state: &AppState, title: &str, body: &str) {
+    if let Err(e) = state.app_handle.notification().builder().title(title).body(body).show() {
+        log::warn!("Failed to show task reminder: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Task;
+    use chrono::{NaiveTime, TimeDelta};
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 21).unwrap()
+    }
+
+    fn at(date: NaiveDate, hour: u32) -> DateTime<Utc> {
+        Local
+            .from_local_datetime(&date.and_hms_opt(hour, 0, 0).unwrap())
+            .earliest()
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn occurrence(id: &str, date: NaiveDate, hour: u32) -> TaskOccurrence {
+        TaskOccurrence {
+            task: Task {
+                id: TaskId::from(id),
+                content: id.to_string(),
+                completed: false,
+                assigned_date: Some(date.to_string()),
+                priority: None,
+                tags: Vec::new(),
+                recurrence: None,
+                due_time: NaiveTime::from_hms_opt(hour, 0, 0),
+                created_at: None,
+                updated_at: None,
+            },
+            date,
+            completed: false,
+        }
+    }
+
+    fn ids(due: &[TaskReminder]) -> Vec<&str> {
+        due.iter().map(|reminder| reminder.task.id.as_str()).collect()
+    }
+
+    #[test]
+    fn due_and_overdue_occurrences() {
+        let yesterday = today().pred_opt().unwrap();
+        let occurrences = vec![
+            occurrence("missed", yesterday, 9),
+            occurrence("due", today(), 9),
+            occurrence("later", today(), 11),
+        ];
+
+        let (due, next) = due_reminders(occurrences, &HashMap::new(), at(today(), 10), today());
+
+        assert_eq!(ids(&due), ["missed", "due"]);
+        assert!(due[0].overdue);
+        assert!(!due[1].overdue);
+        assert_eq!(next, Some(at(today(), 11)));
+    }
+
+    #[test]
+    fn snoozed_and_shown_reminders() {
+        let now = at(today(), 10);
+        let snoozed_until = now + TimeDelta::minutes(10);
+        let reminders = HashMap::from([
+            (
+                (TaskId::from("snoozed"), today()),
+                Reminder {
+                    remind_at: snoozed_until,
+                    shown: false,
+                },
+            ),
+            (
+                (TaskId::from("shown"), today()),
+                Reminder {
+                    remind_at: at(today(), 9),
+                    shown: true,
+                },
+            ),
+        ]);
+        let occurrences = vec![occurrence("snoozed", today(), 9), occurrence("shown", today(), 9)];
+
+        let (due, next) = due_reminders(occurrences.clone(), &reminders, now, today());
+        assert!(due.is_empty());
+        assert_eq!(next, Some(snoozed_until));
+
+        let (due, _) = due_reminders(occurrences, &reminders, snoozed_until, today());
+        assert_eq!(ids(&due), ["snoozed"]);
+    }
+
+    #[test]
+    fn completed_and_undated_tasks_are_skipped() {
+        let mut completed = occurrence("completed", today(), 9);
+        completed.completed = true;
+        let mut undated = occurrence("undated", today(), 9);
+        undated.task.assigned_date = None;
+
+        let (due, next) = due_reminders(vec![completed, undated], &HashMap::new(), at(today(), 10), today());
+
+        assert!(due.is_empty());
+        assert_eq!(next, None);
+    }
+}
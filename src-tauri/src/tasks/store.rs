@@ -0,0 +1,768 @@
+use crate::api::TaskRecord;
+use crate::error::AppError;
+use crate::models::{NewTask, Priority, Recurrence, Task, TaskChanges, TaskId};
+use chrono::{DateTime, NaiveDate, NaiveTime, SecondsFormat, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+// One step per schema version, append a step when the schema changes. Step
+// `n` takes the database from `user_version` n - 1 to n.
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS tasks (
+        id TEXT PRIMARY KEY,
+        remote_id TEXT UNIQUE,
+        content TEXT NOT NULL,
+        completed INTEGER NOT NULL,
+        assigned_date TEXT,
+        created_at TEXT,
+        updated_at TEXT,
+        sync_state TEXT NOT NULL
+    );",
+    "ALTER TABLE tasks ADD COLUMN priority TEXT;
+     ALTER TABLE tasks ADD COLUMN tags TEXT NOT NULL DEFAULT '[]';",
+    "ALTER TABLE tasks ADD COLUMN recurrence TEXT;
+     CREATE TABLE IF NOT EXISTS task_completions (
+        task_id TEXT NOT NULL,
+        date TEXT NOT NULL,
+        PRIMARY KEY (task_id, date)
+     );",
+    "ALTER TABLE tasks ADD COLUMN due_time TEXT;
+     CREATE TABLE IF NOT EXISTS task_reminders (
+        task_id TEXT NOT NULL,
+        date TEXT NOT NULL,
+        remind_at TEXT NOT NULL,
+        shown INTEGER NOT NULL,
+        PRIMARY KEY (task_id, date)
+     );",
+];
+
+const DUE_TIME_FORMAT: &str = "%H:%M";
+
+const COLUMNS: &str =
+    "id, remote_id, content, completed, assigned_date, created_at, updated_at, sync_state, priority, tags, recurrence, due_time";
+
+/// Whether a task's local copy still has to reach the backend, and how.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncState {
+    Synced,
+    /// Created locally, the backend hasn't seen it yet
+    Created,
+    Updated,
+    /// Deleted locally, kept until the delete reaches the backend
+    Deleted,
+}
+
+impl SyncState {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Synced => "synced",
+            Self::Created => "created",
+            Self::Updated => "updated",
+            Self::Deleted => "deleted",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "created" => Self::Created,
+            "updated" => Self::Updated,
+            "deleted" => Self::Deleted,
+            _ => Self::Synced,
+        }
+    }
+}
+
+/// A task as stored, with what the sync engine needs to push it.
+#[derive(Debug, Clone)]
+pub struct StoredTask {
+    pub task: Task,
+    /// The worker's ID, set once the task has reached the backend
+    pub remote_id: Option<TaskId>,
+    pub sync_state: SyncState,
+}
+
+impl StoredTask {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            task: Task {
+                id: TaskId::from(row.get::<_, String>(0)?),
+                content: row.get(2)?,
+                completed: row.get(3)?,
+                assigned_date: row.get(4)?,
+                priority: row.get::<_, Option<String>>(8)?.as_deref().and_then(Priority::parse),
+                tags: serde_json::from_str(&row.get::<_, String>(9)?).unwrap_or_default(),
+                recurrence: row
+                    .get::<_, Option<String>>(10)?
+                    .and_then(|rule| Recurrence::from_rrule(&rule).ok()),
+                due_time: row
+                    .get::<_, Option<String>>(11)?
+                    .and_then(|time| NaiveTime::parse_from_str(&time, DUE_TIME_FORMAT).ok()),
+                created_at: row.get(5)?,
+                updated_at: row.get(6)?,
+            },
+            remote_id: row.get::<_, Option<String>>(1)?.map(TaskId::from),
+            sync_state: SyncState::parse(&row.get::<_, String>(7)?),
+        })
+    }
+}
+
+/// The state of an occurrence's reminder, see `TaskStore::reminders`.
+#[derive(Debug, Clone, Copy)]
+pub struct Reminder {
+    /// When it was shown, or when a snooze ends
+    pub remind_at: DateTime<Utc>,
+    pub shown: bool,
+}
+
+/// What happened to the local copy when a pushed change was acknowledged.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Acknowledged {
+    /// The row now matches the backend
+    Synced,
+    /// The task was edited again while the push was in flight, the newer
+    /// edit stays pending
+    StillPending,
+    /// The task was deleted while its create was in flight
+    Gone,
+}
+
+/// The active profile's tasks, kept in SQLite so the tasks page works
+/// offline. Task commands read and write here, the sync loop moves pending
+/// changes to and from the backend.
+pub struct TaskStore {
+    conn: Mutex<Connection>,
+}
+
+impl TaskStore {
+    pub fn open(path: &Path) -> Result<Self, AppError> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        Self::init(Connection::open(path)?)
+    }
+
+    #[cfg(test)]
+    fn in_memory() -> Result<Self, AppError> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(mut conn: Connection) -> Result<Self, AppError> {
+        migrate(&mut conn)?;
+        // Completions and reminders outlive their task when it's deleted or
+        // purged, they're dropped here rather than on every path that
+        // removes a task
+        conn.execute_batch(
+            "DELETE FROM task_completions WHERE task_id NOT IN (SELECT id FROM tasks);
+             DELETE FROM task_reminders WHERE task_id NOT IN (SELECT id FROM tasks);",
+        )?;
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    pub fn get(&self, id: &TaskId) -> Result<Task, AppError> {
+        let conn = self.conn.lock()?;
+        find(&conn, id)?
+            .filter(|stored| stored.sync_state != SyncState::Deleted)
+            .map(|stored| stored.task)
+            .ok_or_else(|| not_found(id))
+    }
+
+    /// Every task that hasn't been deleted, oldest first.
+    pub fn list(&self) -> Result<Vec<Task>, AppError> {
+        let conn = self.conn.lock()?;
+        let mut statement = conn.prepare(&format!(
+            "SELECT {} FROM tasks WHERE sync_state != 'deleted' ORDER BY created_at, id",
+            COLUMNS
+        ))?;
+        let tasks = statement
+            .query_map([], StoredTask::from_row)?
+            .map(|row| row.map(|stored| stored.task))
+            .collect::<Result<_, _>>()?;
+        Ok(tasks)
+    }
+
+    pub fn create(&self, task: NewTask) -> Result<Task, AppError> {
+        let now = now();
+        let task = Task {
+            id: TaskId::from(Uuid::new_v4().to_string()),
+            content: task.content,
+            completed: false,
+            assigned_date: Some(task.assigned_date),
+            priority: task.priority,
+            tags: task.tags,
+            recurrence: task.recurrence,
+            due_time: task.due_time,
+            created_at: Some(now.clone()),
+            updated_at: Some(now),
+        };
+
+        let conn = self.conn.lock()?;
+        insert(&conn, &task, None, SyncState::Created)?;
+        Ok(task)
+    }
+
+    pub fn update(&self, id: &TaskId, changes: TaskChanges) -> Result<Task, AppError> {
+        let conn = self.conn.lock()?;
+        let mut stored = find(&conn, id)?
+            .filter(|stored| stored.sync_state != SyncState::Deleted)
+            .ok_or_else(|| not_found(id))?;
+
+        let task = &mut stored.task;
+        if let Some(content) = changes.content {
+            task.content = content;
+        }
+        if let Some(completed) = changes.completed {
+            task.completed = completed;
+        }
+        if let Some(assigned_date) = changes.assigned_date {
+            task.assigned_date = Some(assigned_date);
+        }
+        if let Some(priority) = changes.priority {
+            task.priority = Some(priority);
+        }
+        if let Some(tags) = changes.tags {
+            task.tags = tags;
+        }
+        // A reminder already shown or snoozed belongs to the old schedule
+        let rescheduled =
+            changes.assigned_date.is_some() || changes.recurrence.is_some() || changes.due_time.is_some();
+        if let Some(recurrence) = changes.recurrence {
+            task.recurrence = recurrence;
+        }
+        if let Some(due_time) = changes.due_time {
+            task.due_time = due_time;
+        }
+        task.updated_at = Some(now());
+        if rescheduled {
+            conn.execute("DELETE FROM task_reminders WHERE task_id = ?1", params![id.as_str()])?;
+        }
+
+        // A task the backend hasn't seen yet is still a create
+        let sync_state = match stored.sync_state {
+            SyncState::Created => SyncState::Created,
+            _ => SyncState::Updated,
+        };
+        write(&conn, &stored.task, stored.remote_id.as_ref(), sync_state)?;
+        Ok(stored.task)
+    }
+
+    pub fn delete(&self, id: &TaskId) -> Result<(), AppError> {
+        let conn = self.conn.lock()?;
+        let stored = find(&conn, id)?
+            .filter(|stored| stored.sync_state != SyncState::Deleted)
+            .ok_or_else(|| not_found(id))?;
+
+        match stored.remote_id {
+            // Never reached the backend, nothing to tell it
+            None => {
+                conn.execute("DELETE FROM tasks WHERE id = ?1", params![id.as_str()])?;
+            }
+            Some(_) => {
+                conn.execute(
+                    "UPDATE tasks SET sync_state = 'deleted', updated_at = ?2 WHERE id = ?1",
+                    params![id.as_str(), now()],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Forgets every task, pending changes included.
+    pub fn clear(&self) -> Result<(), AppError> {
+        self.conn
+            .lock()?
+            .execute_batch("DELETE FROM tasks; DELETE FROM task_completions; DELETE FROM task_reminders;")?;
+        Ok(())
+    }
+
+    /// The days on which occurrences of repeating tasks were completed,
+    /// between `from` and `to` inclusive.
+    pub fn completions(&self, from: NaiveDate, to: NaiveDate) -> Result<HashSet<(TaskId, NaiveDate)>, AppError> {
+        let conn = self.conn.lock()?;
+        let mut statement =
+            conn.prepare("SELECT task_id, date FROM task_completions WHERE date BETWEEN ?1 AND ?2")?;
+        let completions = statement
+            .query_map(params![from.to_string(), to.to_string()], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter_map(|(id, date)| Some((TaskId::from(id), date.parse().ok()?)))
+            .collect();
+        Ok(completions)
+    }
+
+    /// Reminders already shown or snoozed for occurrences between `from` and
+    /// `to` inclusive. An occurrence without one hasn't been reminded of yet.
+    pub fn reminders(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<HashMap<(TaskId, NaiveDate), Reminder>, AppError> {
+        let conn = self.conn.lock()?;
+        let mut statement =
+            conn.prepare("SELECT task_id, date, remind_at, shown FROM task_reminders WHERE date BETWEEN ?1 AND ?2")?;
+        let reminders = statement
+            .query_map(params![from.to_string(), to.to_string()], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, bool>(3)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter_map(|(id, date, remind_at, shown)| {
+                let remind_at = DateTime::parse_from_rfc3339(&remind_at).ok()?.with_timezone(&Utc);
+                Some(((TaskId::from(id), date.parse().ok()?), Reminder { remind_at, shown }))
+            })
+            .collect();
+        Ok(reminders)
+    }
+
+    /// Records that the reminder for an occurrence was shown, so it isn't
+    /// shown again.
+    pub fn mark_reminded(&self, id: &TaskId, date: NaiveDate) -> Result<(), AppError> {
+        self.set_reminder(id, date, Utc::now(), true)
+    }
+
+    /// Shows the reminder for an occurrence again at `until`.
+    pub fn snooze(&self, id: &TaskId, date: NaiveDate, until: DateTime<Utc>) -> Result<(), AppError> {
+        self.set_reminder(id, date, until, false)
+    }
+
+    fn set_reminder(&self, id: &TaskId, date: NaiveDate, remind_at: DateTime<Utc>, shown: bool) -> Result<(), AppError> {
+        self.conn.lock()?.execute(
+            "INSERT OR REPLACE INTO task_reminders (task_id, date, remind_at, shown) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                id.as_str(),
+                date.to_string(),
+                remind_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+                shown,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Marks one occurrence of a repeating task done or not done. These
+    /// aren't synced, the worker has no notion of occurrences.
+    pub fn set_completion(&self, id: &TaskId, date: NaiveDate, completed: bool) -> Result<(), AppError> {
+        let conn = self.conn.lock()?;
+        if completed {
+            conn.execute(
+                "INSERT OR IGNORE INTO task_completions (task_id, date) VALUES (?1, ?2)",
+                params![id.as_str(), date.to_string()],
+            )?;
+        } else {
+            conn.execute(
+                "DELETE FROM task_completions WHERE task_id = ?1 AND date = ?2",
+                params![id.as_str(), date.to_string()],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Changes the backend hasn't seen yet, in the order they were made.
+    pub fn pending(&self) -> Result<Vec<StoredTask>, AppError> {
+        let conn = self.conn.lock()?;
+        let mut statement = conn.prepare(&format!(
+            "SELECT {} FROM tasks WHERE sync_state != 'synced' ORDER BY updated_at, id",
+            COLUMNS
+        ))?;
+        let pending = statement
+            .query_map([], StoredTask::from_row)?
+            .collect::<Result<_, _>>()?;
+        Ok(pending)
+    }
+
+    /// Records the backend's copy of a pushed task. `pushed` is the local copy
+    /// that was sent, if the task has changed since only the remote ID is
+    /// kept so the newer edit is pushed next time.
+    pub fn acknowledge(&self, pushed: &StoredTask, record: TaskRecord) -> Result<Acknowledged, AppError> {
+        let conn = self.conn.lock()?;
+        let Some(current) = find(&conn, &pushed.task.id)? else {
+            return Ok(Acknowledged::Gone);
+        };
+
+        // A create whose earlier response was lost can already have been
+        // pulled in as a separate row, this row replaces it
+        conn.execute(
+            "DELETE FROM tasks WHERE remote_id = ?1 AND id != ?2",
+            params![record.id.as_str(), pushed.task.id.as_str()],
+        )?;
+
+        if current.task.updated_at != pushed.task.updated_at {
+            let sync_state = match current.sync_state {
+                SyncState::Created => SyncState::Updated,
+                state => state,
+            };
+            write(&conn, &current.task, Some(&record.id), sync_state)?;
+            return Ok(Acknowledged::StillPending);
+        }
+
+        let remote_id = record.id.clone();
+        let task = Task {
+            id: current.task.id,
+            priority: current.task.priority,
+            tags: current.task.tags,
+            recurrence: current.task.recurrence,
+            due_time: current.task.due_time,
+            ..Task::from(record)
+        };
+        write(&conn, &task, Some(&remote_id), SyncState::Synced)?;
+        Ok(Acknowledged::Synced)
+    }
+
+    /// Drops a task whose delete has reached the backend.
+    pub fn purge(&self, id: &TaskId) -> Result<(), AppError> {
+        let conn = self.conn.lock()?;
+        conn.execute(
+            "DELETE FROM tasks WHERE id = ?1 AND sync_state = 'deleted'",
+            params![id.as_str()],
+        )?;
+        Ok(())
+    }
+
+    /// Turns a pending update to a task that no longer exists on the backend
+    /// back into a create, so the local edit isn't lost.
+    pub fn recreate(&self, id: &TaskId) -> Result<(), AppError> {
+        let conn = self.conn.lock()?;
+        conn.execute(
+            "UPDATE tasks SET remote_id = NULL, sync_state = 'created' WHERE id = ?1 AND sync_state = 'updated'",
+            params![id.as_str()],
+        )?;
+        Ok(())
+    }
+
+    /// Merges the backend's full task list into the store. Conflicts go to
+    /// whichever side was updated last. Returns whether any task the user
+    /// can see changed.
+    pub fn merge_remote(&self, records: Vec<TaskRecord>) -> Result<bool, AppError> {
+        let mut conn = self.conn.lock()?;
+        let tx = conn.transaction()?;
+        let mut changed = false;
+        let mut seen = HashSet::new();
+
+        for record in records {
+            seen.insert(record.id.clone());
+            let existing = tx
+                .query_row(
+                    &format!("SELECT {} FROM tasks WHERE remote_id = ?1", COLUMNS),
+                    params![record.id.as_str()],
+                    StoredTask::from_row,
+                )
+                .optional()?;
+
+            let remote_id = record.id.clone();
+            match existing {
+                None => {
+                    let task = Task {
+                        id: TaskId::from(Uuid::new_v4().to_string()),
+                        ..Task::from(record)
+                    };
+                    insert(&tx, &task, Some(&remote_id), SyncState::Synced)?;
+                    changed = true;
+                }
+                Some(local) => {
+                    let remote_wins = match local.sync_state {
+                        SyncState::Synced => true,
+                        _ => is_newer(record.updated_at.as_deref(), local.task.updated_at.as_deref()),
+                    };
+                    if !remote_wins {
+                        continue;
+                    }
+
+                    let task = Task {
+                        id: local.task.id.clone(),
+                        priority: local.task.priority,
+                        tags: local.task.tags.clone(),
+                        recurrence: local.task.recurrence.clone(),
+                        due_time: local.task.due_time,
+                        ..Task::from(record)
+                    };
+                    if task != local.task || local.sync_state != SyncState::Synced {
+                        write(&tx, &task, Some(&remote_id), SyncState::Synced)?;
+                        changed |= task != local.task || local.sync_state == SyncState::Deleted;
+                    }
+                }
+            }
+        }
+
+        // Tasks the backend no longer has were deleted elsewhere
+        let mut statement = tx.prepare(&format!("SELECT {} FROM tasks WHERE remote_id IS NOT NULL", COLUMNS))?;
+        let known: Vec<StoredTask> = statement
+            .query_map([], StoredTask::from_row)?
+            .collect::<Result<_, _>>()?;
+        drop(statement);
+
+        for local in known {
+            if local.remote_id.as_ref().is_some_and(|id| seen.contains(id)) {
+                continue;
+            }
+            match local.sync_state {
+                // An edit made here after the delete brings the task back
+                SyncState::Updated => {
+                    tx.execute(
+                        "UPDATE tasks SET remote_id = NULL, sync_state = 'created' WHERE id = ?1",
+                        params![local.task.id.as_str()],
+                    )?;
+                }
+                SyncState::Deleted => {
+                    tx.execute("DELETE FROM tasks WHERE id = ?1", params![local.task.id.as_str()])?;
+                }
+                SyncState::Synced | SyncState::Created => {
+                    tx.execute("DELETE FROM tasks WHERE id = ?1", params![local.task.id.as_str()])?;
+                    changed = true;
+                }
+            }
+        }
+
+        tx.commit()?;
+        Ok(changed)
+    }
+}
+
+// Each step commits together with its `user_version`, so one that fails
+// halfway is rolled back and runs again in full on the next start
+fn migrate(conn: &mut Connection) -> Result<(), AppError> {
+    let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for (index, step) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(step)?;
+        tx.pragma_update(None, "user_version", index + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+fn find(conn: &Connection, id: &TaskId) -> Result<Option<StoredTask>, AppError> {
+    let stored = conn
+        .query_row(
+            &format!("SELECT {} FROM tasks WHERE id = ?1", COLUMNS),
+            params![id.as_str()],
+            StoredTask::from_row,
+        )
+        .optional()?;
+    Ok(stored)
+}
+
+fn insert(conn: &Connection, task: &Task, remote_id: Option<&TaskId>, sync_state: SyncState) -> rusqlite::Result<()> {
+    conn.execute(
+        &format!("INSERT INTO tasks ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)", COLUMNS),
+        params![
+            task.id.as_str(),
+            remote_id.map(TaskId::as_str),
+            task.content,
+            task.completed,
+            task.assigned_date,
+            task.created_at,
+            task.updated_at,
+            sync_state.as_str(),
+            task.priority.map(Priority::as_str),
+            tags_json(&task.tags),
+            task.recurrence.as_ref().map(Recurrence::to_rrule),
+            task.due_time.map(|time| time.format(DUE_TIME_FORMAT).to_string()),
+        ],
+    )?;
+    Ok(())
+}
+
+fn write(conn: &Connection, task: &Task, remote_id: Option<&TaskId>, sync_state: SyncState) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE tasks SET remote_id = ?2, content = ?3, completed = ?4, assigned_date = ?5, created_at = ?6,
+            updated_at = ?7, sync_state = ?8, priority = ?9, tags = ?10, recurrence = ?11,
+            due_time = ?12
+         WHERE id = ?1",
+        params![
+            task.id.as_str(),
+            remote_id.map(TaskId::as_str),
+            task.content,
+            task.completed,
+            task.assigned_date,
+            task.created_at,
+            task.updated_at,
+            sync_state.as_str(),
+            task.priority.map(Priority::as_str),
+            tags_json(&task.tags),
+            task.recurrence.as_ref().map(Recurrence::to_rrule),
+            task.due_time.map(|time| time.format(DUE_TIME_FORMAT).to_string()),
+        ],
+    )?;
+    Ok(())
+}
+
+fn tags_json(tags: &[String]) -> String {
+    serde_json::to_string(tags).unwrap_or_else(|_| "[]".to_string())
+}
+
+fn not_found(id: &TaskId) -> AppError {
+    AppError::NotFound(format!("No task with ID {}", id))
+}
+
+fn now() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+// Whether the remote copy was updated after the local one. A timestamp that
+// can't be parsed loses, and the local copy wins a tie since it's the one
+// still waiting to be pushed.
+fn is_newer(remote: Option<&str>, local: Option<&str>) -> bool {
+    let parse = |value: Option<&str>| value.and_then(|value| DateTime::parse_from_rfc3339(value).ok());
+    match (parse(remote), parse(local)) {
+        (Some(remote), Some(local)) => remote > local,
+        (Some(_), None) => true,
+        (None, _) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_task(content: &str) -> NewTask {
+        NewTask {
+            content: content.to_string(),
+            assigned_date: "2026-10-18".to_string(),
+            priority: None,
+            tags: Vec::new(),
+            recurrence: None,
+            due_time: None,
+        }
+    }
+
+    fn record(id: &str, content: &str, updated_at: &str) -> TaskRecord {
+        TaskRecord {
+            id: TaskId::from(id),
+            task: content.to_string(),
+            done: false,
+            assigned_date: Some("2026-10-18".to_string()),
+            user_id: None,
+            created_at: Some("2020-01-01T00:00:00Z".to_string()),
+            updated_at: Some(updated_at.to_string()),
+        }
+    }
+
+    // A task pulled from the backend, as the sync loop would have left it
+    fn synced(store: &TaskStore, id: &str, content: &str) -> Task {
+        store.merge_remote(vec![record(id, content, "2020-01-01T00:00:00Z")]).unwrap();
+        store.list().unwrap().into_iter().find(|task| task.content == content).unwrap()
+    }
+
+    #[test]
+    fn older_databases_are_migrated_step_by_step() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0]).unwrap();
+        conn.pragma_update(None, "user_version", 1).unwrap();
+
+        let store = TaskStore::init(conn).unwrap();
+        store.create(new_task("Buy milk")).unwrap();
+
+        let conn = store.conn.lock().unwrap();
+        let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+    }
+
+    #[test]
+    fn merge_overwrites_synced_rows() {
+        let store = TaskStore::in_memory().unwrap();
+        synced(&store, "r1", "Buy milk");
+
+        // Even an older remote copy wins, nothing local is waiting to go out
+        let changed = store
+            .merge_remote(vec![record("r1", "Buy oat milk", "2019-01-01T00:00:00Z")])
+            .unwrap();
+
+        assert!(changed);
+        let tasks = store.list().unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].content, "Buy oat milk");
+        assert!(store.pending().unwrap().is_empty());
+    }
+
+    #[test]
+    fn merge_keeps_newer_local_edits() {
+        let store = TaskStore::in_memory().unwrap();
+        let task = synced(&store, "r1", "Buy milk");
+        let changes = TaskChanges {
+            content: Some("Buy bread".to_string()),
+            ..Default::default()
+        };
+        store.update(&task.id, changes).unwrap();
+
+        let changed = store
+            .merge_remote(vec![record("r1", "Buy milk", "2020-01-01T00:00:00Z")])
+            .unwrap();
+
+        assert!(!changed);
+        assert_eq!(store.get(&task.id).unwrap().content, "Buy bread");
+        let pending = store.pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].sync_state, SyncState::Updated);
+    }
+
+    #[test]
+    fn editing_a_task_deleted_remotely_creates_it_again() {
+        let store = TaskStore::in_memory().unwrap();
+        let task = synced(&store, "r1", "Buy milk");
+        let changes = TaskChanges {
+            content: Some("Buy bread".to_string()),
+            ..Default::default()
+        };
+        store.update(&task.id, changes).unwrap();
+
+        store.merge_remote(Vec::new()).unwrap();
+
+        assert_eq!(store.get(&task.id).unwrap().content, "Buy bread");
+        let pending = store.pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].sync_state, SyncState::Created);
+        assert!(pending[0].remote_id.is_none());
+    }
+
+    #[test]
+    fn deleted_rows_are_purged() {
+        let store = TaskStore::in_memory().unwrap();
+        let milk = synced(&store, "r1", "Buy milk");
+        let bread = synced(&store, "r2", "Buy bread");
+        store.delete(&milk.id).unwrap();
+        store.delete(&bread.id).unwrap();
+        assert!(store.list().unwrap().is_empty());
+        assert_eq!(store.pending().unwrap().len(), 2);
+
+        // Once the delete reached the backend, or when the backend no longer
+        // has the task anyway
+        store.purge(&milk.id).unwrap();
+        let changed = store.merge_remote(Vec::new()).unwrap();
+
+        assert!(!changed);
+        assert!(store.pending().unwrap().is_empty());
+        assert!(store.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn acknowledge_replaces_a_create_pulled_in_before_its_response() {
+        let store = TaskStore::in_memory().unwrap();
+        let task = store.create(new_task("Buy milk")).unwrap();
+        let pushed = store.pending().unwrap().remove(0);
+
+        // The create reached the backend but its response was lost, so the
+        // next pull brings it in as a row of its own
+        store
+            .merge_remote(vec![record("r1", "Buy milk", "2020-01-01T00:00:00Z")])
+            .unwrap();
+        assert_eq!(store.list().unwrap().len(), 2);
+
+        let acknowledged = store
+            .acknowledge(&pushed, record("r1", "Buy milk", "2020-01-01T00:00:00Z"))
+            .unwrap();
+
+        assert_eq!(acknowledged, Acknowledged::Synced);
+        let tasks = store.list().unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].id, task.id);
+        assert!(store.pending().unwrap().is_empty());
+    }
+}
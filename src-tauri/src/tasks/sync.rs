@@ -0,0 +1,132 @@
+use crate::api::{ApiError, CreateTaskRequest, JeffApiClient, UpdateTaskRequest};
+use crate::error::AppError;
+use crate::state::AppState;
+use crate::tasks::store::{Acknowledged, StoredTask, SyncState, TaskStore};
+use crate::AppHandle;
+use std::time::Duration;
+use tauri::{Emitter, EventTarget, Manager};
+
+// How often the store syncs when nothing asks for it sooner
+const SYNC_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Runs a sync whenever `AppState::request_sync` is called, and every
+/// `SYNC_INTERVAL` otherwise so changes made elsewhere show up. Failures are
+/// left for the next run, pending changes stay in the store until then.
+pub fn spawn_sync_loop(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let state = app_handle.state::<AppState>();
+            match sync(&state).await {
+                Ok(()) => {}
+                Err(AppError::Unauthenticated | AppError::Cancelled) => {}
+                Err(e) if e.retryable() => log::info!("Task sync postponed: {}", e),
+                Err(e) => log::warn!("Task sync failed: {}", e),
+            }
+
+            tokio::select! {
+                _ = state.sync_requested.notified() => {}
+                _ = tokio::time::sleep(SYNC_INTERVAL) => {}
+            }
+        }
+    });
+}
+
+/// Pulls the backend's tasks and merges them in, then pushes what's still
+/// pending. Pulling first lets a newer remote edit win over an older offline
+/// one instead of being overwritten by it.
+async fn sync(state: &AppState) -> Result<(), AppError> {
+    let store = state.task_store()?;
+    let api = state.api().await?;
+
+    let remote = api.list_tasks().await?;
+    let mut changed = store.merge_remote(remote)?;
+    let pushed = push_pending(state, &api, &store, &mut changed).await;
+
+    if changed {
+        emit_tasks_changed(state, &store)?;
+    }
+    pushed
+}
+
+// Stops at the first failure that retrying could fix, the rest wait for the
+// next run. A change the backend rejects outright is skipped so it can't hold
+// up the others.
+async fn push_pending(
+    state: &AppState,
+    api: &JeffApiClient,
+    store: &TaskStore,
+    changed: &mut bool,
+) -> Result<(), AppError> {
+    for pending in store.pending()? {
+        let id = pending.task.id.clone();
+        match push(state, api, store, pending).await {
+            Ok(visible) => *changed |= visible,
+            Err(e) if e.retryable() || matches!(e, AppError::Unauthenticated | AppError::Cancelled) => return Err(e),
+            Err(e) => log::warn!("Backend rejected the change to task {}: {}", id, e),
+        }
+    }
+    Ok(())
+}
+
+// Sends one pending change. Returns whether the store changed in a way the
+// user can see.
+async fn push(state: &AppState, api: &JeffApiClient, store: &TaskStore, pending: StoredTask) -> Result<bool, AppError> {
+    let task = &pending.task;
+    match (pending.sync_state, pending.remote_id.as_ref()) {
+        (SyncState::Created, _) => {
+            let request = CreateTaskRequest {
+                task: task.content.clone(),
+                done: task.completed,
+                user_id: state.current_user_id()?,
+                assigned_date: task.assigned_date.clone().unwrap_or_default(),
+            };
+
+            // The local ID is the idempotency key, so a create whose response
+            // was lost isn't applied twice when the next sync retries it
+            let record = api.create_task(&request, task.id.as_str()).await?;
+            let remote_id = record.id.clone();
+            if store.acknowledge(&pending, record)? == Acknowledged::Gone {
+                api.delete_task(&remote_id).await?;
+                return Ok(false);
+            }
+            Ok(true)
+        }
+        (SyncState::Updated, Some(remote_id)) => {
+            let request = UpdateTaskRequest {
+                task: Some(task.content.clone()),
+                done: Some(task.completed),
+                day: task.assigned_date.clone(),
+            };
+            match api.update_task(remote_id, &request).await {
+                Ok(record) => Ok(store.acknowledge(&pending, record)? != Acknowledged::Gone),
+                Err(ApiError::Server { status: 404, .. }) => {
+                    store.recreate(&task.id)?;
+                    state.request_sync();
+                    Ok(false)
+                }
+                Err(e) => Err(e.into()),
+            }
+        }
+        (SyncState::Deleted, Some(remote_id)) => {
+            match api.delete_task(remote_id).await {
+                Ok(()) | Err(ApiError::Server { status: 404, .. }) => store.purge(&task.id)?,
+                Err(e) => return Err(e.into()),
+            }
+            Ok(false)
+        }
+        (sync_state, remote_id) => {
+            log::warn!("Skipping task {} in state {:?} with remote ID {:?}", task.id, sync_state, remote_id);
+            Ok(false)
+        }
+    }
+}
+
+/// Sends the store's tasks to the frontend as `tasks-changed`, and has the
+/// reminder loop catch up with them.
+pub fn emit_tasks_changed(state: &AppState, store: &TaskStore) -> Result<(), AppError> {
+    state
+        .app_handle
+        .emit_to(EventTarget::any(), "tasks-changed", store.list()?)?;
+    state.reschedule_reminders();
+    Ok(())
+}
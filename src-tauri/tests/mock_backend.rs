@@ -0,0 +1,398 @@
+//! Drives the API client every command goes through, and the commands
+//! themselves on Tauri's mock runtime, against the in-process mock backend.
+//! Run with `cargo test --features test-runtime`.
+
+use jeff_ai_lib::api::{
+    ApiError, CaptureUserRequest, CreateTaskRequest, JeffApiClient, RefineTextRequest, RefineTextResponse,
+    TranscribeRequest, Units, UpdateTaskRequest, UserSettings, UserSettingsRequest,
+};
+use jeff_ai_lib::mock::{MockBackend, MOCK_ACCESS_TOKEN, MOCK_REFINED, MOCK_TRANSCRIPTION};
+use jeff_ai_lib::models::{NewTask, Task, TaskChanges, TaskParser, TaskPreview};
+use jeff_ai_lib::{build_mock_app, AppRuntime};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+use tauri::ipc::{CallbackFn, InvokeBody};
+use tauri::webview::InvokeRequest;
+use tauri::WebviewWindow;
+use tempfile::TempDir;
+use tokio_util::sync::CancellationToken;
+
+async fn setup() -> (MockBackend, JeffApiClient) {
+    let backend = MockBackend::start().await.expect("mock backend starts");
+    let client = JeffApiClient::new(backend.url()).with_token(MOCK_ACCESS_TOKEN);
+    (backend, client)
+}
+
+fn create_request(content: &str) -> CreateTaskRequest {
+    let task = NewTask {
+        content: content.to_string(),
+        assigned_date: "2026-10-18".to_string(),
+        priority: None,
+        tags: Vec::new(),
+        recurrence: None,
+        due_time: None,
+    };
+    CreateTaskRequest::new(task, "mock-user".to_string())
+}
+
+// A WAV header followed by `samples` bytes of audio
+fn wav(samples: &[u8]) -> Vec<u8> {
+    let mut audio = vec![0u8; 44];
+    audio[..4].copy_from_slice(b"RIFF");
+    audio.extend_from_slice(samples);
+    audio
+}
+
+#[tokio::test]
+async fn rejects_requests_without_a_token() {
+    let backend = MockBackend::start().await.unwrap();
+    let client = JeffApiClient::new(backend.url());
+
+    let result = client.list_tasks().await;
+
+    assert!(matches!(result, Err(ApiError::Server { status: 401, .. })));
+}
+
+#[tokio::test]
+async fn captures_the_user() {
+    let (_backend, client) = setup().await;
+
+    let response = client
+        .capture_user(&CaptureUserRequest {
+            auth_user_id: "kp_123".to_string(),
+            email: "dev@example.com".to_string(),
+            display_name: "Mock Developer".to_string(),
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(response.user.auth_user_id, "kp_123");
+}
+
+#[tokio::test]
+async fn task_round_trip() {
+    let (backend, client) = setup().await;
+
+    let created = Task::from(client.create_task(&create_request("Buy milk"), "key-1").await.unwrap());
+    assert_eq!(created.content, "Buy milk");
+    assert!(!created.completed);
+    assert_eq!(created.assigned_date.as_deref(), Some("2026-10-18"));
+
+    let changes = TaskChanges {
+        completed: Some(true),
+        assigned_date: Some("2026-10-19".to_string()),
+        ..Default::default()
+    };
+    let updated = Task::from(client.update_task(&created.id, &UpdateTaskRequest::from(changes)).await.unwrap());
+    assert_eq!(updated.id, created.id);
+    assert!(updated.completed);
+    assert_eq!(updated.content, "Buy milk");
+    assert_eq!(updated.assigned_date.as_deref(), Some("2026-10-19"));
+
+    assert_eq!(client.list_tasks().await.unwrap().len(), 1);
+
+    client.delete_task(&created.id).await.unwrap();
+    assert!(backend.tasks().is_empty());
+
+    let missing = client.delete_task(&created.id).await;
+    assert!(matches!(missing, Err(ApiError::Server { status: 404, .. })));
+}
+
+#[tokio::test]
+async fn retried_create_is_applied_once() {
+    let (backend, client) = setup().await;
+    backend.fail_next_after_handling(503);
+
+    let created = client.create_task(&create_request("Call the dentist"), "key-1").await.unwrap();
+
+    assert_eq!(backend.request_count("/api/tasks"), 2);
+    assert_eq!(backend.tasks().len(), 1);
+    assert_eq!(backend.tasks()[0].id, created.id);
+}
+
+#[tokio::test]
+async fn creates_sharing_a_key_are_applied_once() {
+    let (backend, client) = setup().await;
+
+    let first = client.create_task(&create_request("Water the plants"), "local-task").await.unwrap();
+    let second = client.create_task(&create_request("Water the plants"), "local-task").await.unwrap();
+
+    assert_eq!(first.id, second.id);
+    assert_eq!(backend.tasks().len(), 1);
+}
+
+#[tokio::test]
+async fn reads_retry_transient_failures() {
+    let (backend, client) = setup().await;
+    backend.fail_next(503);
+    backend.fail_next(502);
+
+    let tasks = client.list_tasks().await.unwrap();
+
+    assert!(tasks.is_empty());
+    assert_eq!(backend.request_count("/api/tasks"), 3);
+}
+
+#[tokio::test]
+async fn reads_give_up_after_the_last_attempt() {
+    let (backend, client) = setup().await;
+    for _ in 0..3 {
+        backend.fail_next(503);
+    }
+
+    let result = client.list_tasks().await;
+
+    assert!(matches!(result, Err(ApiError::Server { status: 503, .. })));
+    assert_eq!(backend.request_count("/api/tasks"), 3);
+}
+
+#[tokio::test]
+async fn client_errors_are_not_retried() {
+    let (backend, client) = setup().await;
+    backend.fail_next(400);
+
+    let result = client.list_tasks().await;
+
+    assert!(matches!(result, Err(ApiError::Server { status: 400, .. })));
+    assert_eq!(backend.request_count("/api/tasks"), 1);
+}
+
+#[tokio::test]
+async fn transcribes_audio() {
+    let (_backend, client) = setup().await;
+
+    let raw = client
+        .transcribe(TranscribeRequest {
+            audio: wav(&[1, 2, 3, 4]),
+            refine: false,
+            user_id: "mock-user".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(raw.transcription.as_deref(), Some(MOCK_TRANSCRIPTION));
+    assert!(raw.refined.is_none());
+
+    let refined = client
+        .transcribe(TranscribeRequest {
+            audio: wav(&[1, 2, 3, 4]),
+            refine: true,
+            user_id: "mock-user".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(refined.refined.as_deref(), Some(MOCK_REFINED));
+}
+
+#[tokio::test]
+async fn silent_audio_has_no_dialog() {
+    let (_backend, client) = setup().await;
+
+    let response = client
+        .transcribe(TranscribeRequest {
+            audio: wav(&[0; 64]),
+            refine: true,
+            user_id: "mock-user".to_string(),
+        })
+        .await
+        .unwrap();
+
+    assert!(response.no_dialog());
+}
+
+#[tokio::test]
+async fn refinements_are_listed_after_refining() {
+    let (_backend, client) = setup().await;
+
+    let refined = client
+        .refine_text(&RefineTextRequest {
+            original_text: "hello there".to_string(),
+            additional_context: None,
+            user_id: "mock-user".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(refined.refined_text, "Hello there.");
+
+    let refinements = client.list_refinements().await.unwrap();
+    assert_eq!(refinements.len(), 1);
+    assert_eq!(refinements[0].original_text, "hello there");
+}
+
+#[tokio::test]
+async fn settings_are_missing_until_created() {
+    let (_backend, client) = setup().await;
+    assert!(client.get_settings().await.unwrap().is_none());
+
+    let request = UserSettingsRequest {
+        user_id: "mock-user".to_string(),
+        lat: Some(40.7),
+        lon: Some(-74.0),
+        city: Some("New York".to_string()),
+        state: Some("NY".to_string()),
+        country: Some("US".to_string()),
+        units: Units::Imperial,
+        language: "en".to_string(),
+    };
+    client.create_settings(&request).await.unwrap();
+
+    let settings = client.get_settings().await.unwrap().unwrap();
+    assert_eq!(settings.city.as_deref(), Some("New York"));
+    assert_eq!(settings.units, Units::Imperial);
+}
+
+#[tokio::test]
+async fn weather_matches_the_frontend_shape() {
+    let (_backend, client) = setup().await;
+
+    let locations = client.geocode("New York,NY,US").await.unwrap();
+    assert_eq!(locations[0].name, "New York");
+    assert_eq!(locations[0].state.as_deref(), Some("NY"));
+
+    let forecast = client.weather_forecast(40.7, -74.0).await.unwrap();
+    assert_eq!(forecast.data.daily.len(), 7);
+    assert_eq!(forecast.data.hourly.len(), 24);
+}
+
+#[tokio::test]
+async fn cancelled_requests_fail_fast() {
+    let (_backend, client) = setup().await;
+    let cancellation = CancellationToken::new();
+    cancellation.cancel();
+
+    let result = client.with_cancellation(cancellation).list_tasks().await;
+
+    assert!(matches!(result, Err(ApiError::Cancelled)));
+}
+
+// The app with its commands, invoked through IPC the way the frontend calls
+// them. Fields drop in order, so the app goes before the backend it talks to.
+struct TestApp {
+    webview: WebviewWindow<AppRuntime>,
+    _app: tauri::App<AppRuntime>,
+    backend: MockBackend,
+    dir: TempDir,
+}
+
+impl TestApp {
+    fn start() -> Self {
+        // On Tauri's runtime, where the commands and the sync loop run
+        let backend = tauri::async_runtime::block_on(MockBackend::start()).expect("mock backend starts");
+        let dir = tempfile::tempdir().unwrap();
+        let app = build_mock_app(tauri::test::mock_builder(), &backend, dir.path()).expect("app builds");
+        let webview = tauri::WebviewWindowBuilder::new(&app, "main", Default::default())
+            .build()
+            .unwrap();
+        Self {
+            webview,
+            _app: app,
+            backend,
+            dir,
+        }
+    }
+
+    fn invoke<T: DeserializeOwned>(&self, command: &str, args: Value) -> Result<T, Value> {
+        let request = InvokeRequest {
+            cmd: command.to_string(),
+            callback: CallbackFn(0),
+            error: CallbackFn(1),
+            url: "tauri://localhost".parse().unwrap(),
+            body: InvokeBody::Json(args),
+            headers: Default::default(),
+            invoke_key: tauri::test::INVOKE_KEY.to_string(),
+        };
+        tauri::test::get_ipc_response(&self.webview, request)
+            .map(|body| body.deserialize().expect("response has the command's type"))
+    }
+}
+
+#[test]
+fn create_task_command_stores_the_task_and_syncs_it() {
+    let app = TestApp::start();
+
+    let task: Task = app
+        .invoke("create_task", json!({ "task": { "content": "Buy milk", "assignedDate": "2026-10-18" } }))
+        .unwrap();
+    assert_eq!(task.content, "Buy milk");
+
+    let tasks: Vec<Task> = app.invoke("fetch_tasks", json!({})).unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].content, "Buy milk");
+
+    // Creating asks the sync loop to push right away
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while app.backend.tasks().is_empty() {
+        assert!(Instant::now() < deadline, "task never reached the backend");
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    assert_eq!(app.backend.tasks()[0].task, "Buy milk");
+}
+
+#[test]
+fn commands_report_errors_by_kind() {
+    let app = TestApp::start();
+
+    let error = app
+        .invoke::<Task>("create_task", json!({ "task": { "content": " ", "assignedDate": "2026-10-18" } }))
+        .unwrap_err();
+
+    assert_eq!(error["kind"], "validation");
+}
+
+#[test]
+fn refine_text_command_goes_to_the_backend() {
+    let app = TestApp::start();
+
+    let refined: RefineTextResponse = app.invoke("refine_text", json!({ "text": "hello there" })).unwrap();
+
+    assert_eq!(refined.refined_text, "Hello there.");
+    assert_eq!(app.backend.request_count("/api/refinements"), 1);
+}
+
+#[test]
+fn parse_tasks_falls_back_to_local_rules_when_the_model_reply_is_unusable() {
+    let app = TestApp::start();
+
+    // The mock refines text rather than answering with JSON
+    let preview: TaskPreview = app
+        .invoke("parse_tasks", json!({ "transcript": "buy milk then call mom", "useLlm": true }))
+        .unwrap();
+
+    assert_eq!(app.backend.request_count("/api/refinements"), 1);
+    assert_eq!(preview.parser, TaskParser::Local);
+    assert_eq!(preview.drafts.len(), 2);
+}
+
+#[test]
+fn user_settings_commands_go_to_the_backend() {
+    let app = TestApp::start();
+
+    let settings: Option<UserSettings> = app.invoke("get_user_settings", json!({})).unwrap();
+    assert!(settings.is_none());
+
+    let _: UserSettings = app
+        .invoke(
+            "create_user_settings",
+            json!({ "settings": { "city": "New York", "units": "imperial", "language": "en" } }),
+        )
+        .unwrap();
+
+    let settings: Option<UserSettings> = app.invoke("get_user_settings", json!({})).unwrap();
+    let settings = settings.unwrap();
+    assert_eq!(settings.city.as_deref(), Some("New York"));
+    assert_eq!(settings.units, Units::Imperial);
+}
+
+#[test]
+fn failed_recording_start_does_not_block_the_next_one() {
+    let app = TestApp::start();
+    // A file where the recordings dir should be, so setting up the WAV fails
+    let profile_dir = app.dir.path().join("profiles").join("mock");
+    std::fs::create_dir_all(&profile_dir).unwrap();
+    std::fs::write(profile_dir.join("recordings"), b"").unwrap();
+
+    for _ in 0..2 {
+        let error = app.invoke::<()>("start_recording", json!({})).unwrap_err();
+        assert_eq!(error["kind"], "io");
+    }
+}
@@ -1,6 +1,7 @@
-import { useEffect } from 'react';
+import { useEffect, useState } from 'react';
 import * as KindeAuth from '@kinde-oss/kinde-auth-react';
 import { LogIn, UserPlus, Smile } from 'lucide-react';
+import { invoke } from '@tauri-apps/api/core';
 import { Route, Switch } from 'wouter';
 
 import { Button } from '@/components/ui/button';
@@ -10,6 +11,9 @@ import { SidebarTrigger } from '@/components/ui/sidebar';
 import { SidebarProvider } from '@/components/ui/sidebar';
 import { ThemeProvider } from '@/components/theme-provider';
 import { checkForAppUpdates } from '@/helpers/updater';
+import { useSessionSync } from '@/hooks/use-session-sync';
+import { useTaskReminders } from '@/hooks/use-task-reminders';
+import { BackendConfig } from '@/types/commands';
 import Refinements from '@/pages/refinements';
 import Home from '@/pages/home';
 import Search from '@/pages/search';
@@ -37,7 +41,17 @@ function App() {
     checkForAppUpdates();
   }, []);
 
+  // The mock backend comes with a signed-in user, no Kinde login needed
+  const [mockBackend, setMockBackend] = useState(false);
+  useEffect(() => {
+    invoke<BackendConfig>('get_backend_config')
+      .then((config) => setMockBackend(config.mock))
+      .catch((error) => console.error('Error loading backend config:', error));
+  }, []);
+
   const { login, register, isAuthenticated } = KindeAuth.useKindeAuth();
+  const sessionReady = useSessionSync();
+  useTaskReminders();
 
   return (
     <ThemeProvider>
@@ -47,7 +61,7 @@ function App() {
           <main className="flex-1 flex flex-col w-full min-w-0">
             <SidebarTrigger />
             <section className="flex-1 w-full p-2 overflow-auto">
-              {!isAuthenticated ? (
+              {!isAuthenticated && !mockBackend ? (
                 <div className="w-full h-full">
                   <div className="bg-white dark:bg-gray-900 rounded-lg p-6 shadow">
                     <div className="flex items-center gap-3 mb-2">
@@ -79,9 +93,9 @@ function App() {
                     </div>
                   </div>
                 </div>
-              ) : (
+              ) : sessionReady || mockBackend ? (
                 <Router />
-              )}
+              ) : null}
             </section>
             <Toaster />
           </main>
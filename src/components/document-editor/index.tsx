@@ -124,10 +124,9 @@ export const DocumentEditor = ({ content }: DocumentEditorProps) => {
     if (!isAuthenticated || !getToken) {
       return;
     }
-    const token = await getToken();
     send({ type: 'TOGGLE_RECORDER' });
     if (state.matches('recorder')) {
-      invoke('stop_recording', { token, refine: false });
+      invoke('stop_recording', { refine: false });
     } else {
       invoke('start_recording');
     }
@@ -156,10 +155,8 @@ export const DocumentEditor = ({ content }: DocumentEditorProps) => {
       if (!isAuthenticated || !getToken) {
         return;
       }
-      const token = await getToken();
 
       const response = await invoke<RefineTextResponse>('refine_text', {
-        token,
         text,
         context: context !== '' ? context : undefined
       });
@@ -178,10 +175,8 @@ export const DocumentEditor = ({ content }: DocumentEditorProps) => {
       if (!isAuthenticated || !getToken) {
         return;
       }
-      const token = await getToken();
 
       const response = await invoke<MarkdownResponse>('convert_to_markdown', {
-        token,
         html
       });
 
@@ -152,11 +152,10 @@ export const MenuBar = ({ editor }: MenuBarProps) => {
     if (!isAuthenticated || !getToken) {
       return;
     }
-    const token = await getToken();
 
     send({ type: 'TOGGLE_RECORDER' });
     if (state.matches('recorder')) {
-      invoke('stop_recording', { token, refine: false });
+      invoke('stop_recording', { refine: false });
     } else {
       invoke('start_recording');
     }
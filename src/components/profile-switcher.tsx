@@ -0,0 +1,102 @@
+import { useCallback, useEffect, useState } from 'react';
+import { ChevronDown, Plus } from 'lucide-react';
+import { invoke } from '@tauri-apps/api/core';
+
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import {
+  DropdownMenu,
+  DropdownMenuContent,
+  DropdownMenuItem,
+  DropdownMenuTrigger
+} from '@/components/ui/dropdown-menu';
+import { useToast } from '@/hooks/use-toast';
+import { isAppError, ProfileRegistry } from '@/types/commands';
+
+export function ProfileSwitcher() {
+  const { toast } = useToast();
+  const [registry, setRegistry] = useState<ProfileRegistry | null>(null);
+  const [newName, setNewName] = useState('');
+
+  const refresh = useCallback(async () => {
+    try {
+      setRegistry(await invoke<ProfileRegistry>('list_profiles'));
+    } catch (error) {
+      console.error('Error loading profiles:', error);
+    }
+  }, []);
+
+  useEffect(() => {
+    refresh();
+  }, [refresh]);
+
+  const switchTo = async (id: string) => {
+    try {
+      await invoke('switch_profile', { id });
+      await refresh();
+    } catch (error) {
+      console.error('Error switching profile:', error);
+      toast({
+        variant: 'destructive',
+        description: 'Failed to switch profile'
+      });
+    }
+  };
+
+  const create = async () => {
+    try {
+      await invoke('create_profile', { name: newName });
+      setNewName('');
+      await refresh();
+    } catch (error) {
+      toast({
+        variant: 'destructive',
+        description: isAppError(error)
+          ? error.message
+          : 'Failed to create profile'
+      });
+    }
+  };
+
+  if (!registry) return null;
+
+  const active = registry.profiles.find((p) => p.id === registry.active);
+
+  return (
+    <div className="space-y-2 mb-4">
+      <DropdownMenu>
+        <DropdownMenuTrigger asChild>
+          <Button variant="outline" className="w-full justify-between">
+            <span>Profile: {active?.name ?? registry.active}</span>
+            <ChevronDown className="w-4 h-4" />
+          </Button>
+        </DropdownMenuTrigger>
+        <DropdownMenuContent align="start">
+          {registry.profiles.map((profile) => (
+            <DropdownMenuItem
+              key={profile.id}
+              disabled={profile.id === registry.active}
+              onClick={() => switchTo(profile.id)}
+            >
+              {profile.name}
+            </DropdownMenuItem>
+          ))}
+        </DropdownMenuContent>
+      </DropdownMenu>
+      <div className="flex gap-2">
+        <Input
+          placeholder="New profile name"
+          value={newName}
+          onChange={(e) => setNewName(e.target.value)}
+        />
+        <Button
+          variant="outline"
+          disabled={!newName.trim()}
+          onClick={create}
+        >
+          <Plus className="w-4 h-4" />
+        </Button>
+      </div>
+    </div>
+  );
+}
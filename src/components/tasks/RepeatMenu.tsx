@@ -0,0 +1,85 @@
+import { Repeat } from 'lucide-react';
+import { cn } from '@/lib/utils';
+import {
+  DropdownMenu,
+  DropdownMenuContent,
+  DropdownMenuItem,
+  DropdownMenuTrigger
+} from '@/components/ui/dropdown-menu';
+import type { Recurrence } from '@/types/commands';
+
+interface RepeatMenuProps {
+  recurrence: Recurrence | null;
+  onChange: (recurrence: Recurrence | null) => void;
+}
+
+// Weekly and monthly presets repeat on the task's own day
+const presets: { label: string; recurrence: Recurrence | null }[] = [
+  { label: "Doesn't repeat", recurrence: null },
+  {
+    label: 'Daily',
+    recurrence: { frequency: 'daily', interval: 1, days: [] }
+  },
+  {
+    label: 'Every weekday',
+    recurrence: {
+      frequency: 'weekly',
+      interval: 1,
+      days: ['monday', 'tuesday', 'wednesday', 'thursday', 'friday']
+    }
+  },
+  {
+    label: 'Weekly',
+    recurrence: { frequency: 'weekly', interval: 1, days: [] }
+  },
+  {
+    label: 'Monthly',
+    recurrence: { frequency: 'monthly', interval: 1, days: [] }
+  }
+];
+
+export function describeRecurrence(recurrence: Recurrence): string {
+  const { frequency, interval, days } = recurrence;
+  const weekdays =
+    days.length === 5 && !days.includes('saturday') && !days.includes('sunday');
+  if (frequency === 'weekly' && weekdays) {
+    return interval === 1
+      ? 'every weekday'
+      : `every ${interval} weeks on weekdays`;
+  }
+
+  const unit = { daily: 'day', weekly: 'week', monthly: 'month' }[frequency];
+  const every = interval === 1 ? `every ${unit}` : `every ${interval} ${unit}s`;
+  return days.length > 0 && frequency !== 'daily'
+    ? `${every} on ${days.map((day) => day.slice(0, 3)).join(', ')}`
+    : every;
+}
+
+export function RepeatMenu({ recurrence, onChange }: RepeatMenuProps) {
+  return (
+    <DropdownMenu>
+      <DropdownMenuTrigger asChild>
+        <button
+          className={cn(
+            'text-gray-400 hover:text-gray-600 dark:hover:text-gray-300 p-1',
+            recurrence && 'text-orange-500 dark:text-orange-500'
+          )}
+          onClick={(e) => e.stopPropagation()}
+          aria-label="Repeat"
+        >
+          <Repeat className="h-4 w-4" />
+        </button>
+      </DropdownMenuTrigger>
+      <DropdownMenuContent align="end">
+        {presets.map((preset) => (
+          <DropdownMenuItem
+            key={preset.label}
+            onClick={() => onChange(preset.recurrence)}
+          >
+            {preset.label}
+          </DropdownMenuItem>
+        ))}
+      </DropdownMenuContent>
+    </DropdownMenu>
+  );
+}
@@ -1,41 +1,49 @@
 import { cn } from '@/lib/utils';
-import { Task } from './types';
+import type { Recurrence, TaskId } from '@/types/commands';
+import { Occurrence } from './types';
 import { AnimatePresence } from 'motion/react';
+import { describeRecurrence, RepeatMenu } from './RepeatMenu';
 
 interface TaskItemProps {
-  task: Task;
-  deletingTaskId: number | null;
-  onToggle: (id: number) => void;
-  onDelete: (id: number) => void;
-  onLongPressStart: (id: number) => void;
+  occurrence: Occurrence;
+  deletingTaskId: TaskId | null;
+  onToggle: (occurrence: Occurrence) => void;
+  onDelete: (id: TaskId) => void;
+  onRepeatChange: (id: TaskId, recurrence: Recurrence | null) => void;
+  onDueTimeChange: (id: TaskId, time: string | null) => void;
+  onLongPressStart: (id: TaskId) => void;
   onLongPressEnd: () => void;
   onHoverDeleteArea: (isHovering: boolean) => void;
 }
 
 export function TaskItem({
-  task,
+  occurrence,
   deletingTaskId,
   onToggle,
   onDelete,
+  onRepeatChange,
+  onDueTimeChange,
   onLongPressStart,
   onLongPressEnd,
   onHoverDeleteArea
 }: TaskItemProps) {
+  const { task, completed } = occurrence;
+
   return (
     <div className="flex items-start space-x-2">
       <div className="flex-shrink-0 pt-1">
         <input
           type="checkbox"
           className="task-checkbox"
-          checked={task.completed}
-          onChange={() => onToggle(task.id)}
+          checked={completed}
+          onChange={() => onToggle(occurrence)}
         />
       </div>
       <div className="flex-grow relative">
         <div
           className={cn(
             'flex-grow text-gray-900 dark:text-gray-100 text-base select-none',
-            task.completed &&
+            completed &&
               'line-through text-gray-400 dark:text-gray-600 decoration-[#ea580c]',
             deletingTaskId === task.id && 'text-red-500 dark:text-red-500'
           )}
@@ -48,6 +56,20 @@ export function TaskItem({
         >
           {task.content}
         </div>
+        {(task.priority || task.tags.length > 0 || task.recurrence) && (
+          <div className="flex flex-wrap gap-2 text-xs text-gray-500 dark:text-gray-400">
+            {task.priority === 'high' && (
+              <span className="text-red-600 dark:text-red-400">high priority</span>
+            )}
+            {task.priority === 'low' && <span>low priority</span>}
+            {task.tags.map((tag) => (
+              <span key={tag}>#{tag}</span>
+            ))}
+            {task.recurrence && (
+              <span>{describeRecurrence(task.recurrence)}</span>
+            )}
+          </div>
+        )}
         {deletingTaskId === task.id && (
           <AnimatePresence>
             <button
@@ -71,6 +93,21 @@ export function TaskItem({
           </AnimatePresence>
         )}
       </div>
+      {!task.__tempId && (
+        <div className="flex-shrink-0 flex items-center">
+          <input
+            type="time"
+            className="bg-transparent text-xs text-gray-500 dark:text-gray-400"
+            value={task.dueTime?.slice(0, 5) ?? ''}
+            onChange={(e) => onDueTimeChange(task.id, e.target.value || null)}
+            aria-label="Due time"
+          />
+          <RepeatMenu
+            recurrence={task.recurrence}
+            onChange={(recurrence) => onRepeatChange(task.id, recurrence)}
+          />
+        </div>
+      )}
     </div>
   );
 }
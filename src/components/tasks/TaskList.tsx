@@ -1,34 +1,41 @@
-import { Task } from './types';
+import type { Recurrence, TaskId } from '@/types/commands';
+import { Occurrence } from './types';
 import { TaskItem } from './TaskItem';
 
 interface TaskListProps {
-  tasks: Task[];
-  deletingTaskId: number | null;
-  onToggleTask: (id: number) => void;
-  onDeleteTask: (id: number) => void;
-  onLongPressStart: (id: number) => void;
+  occurrences: Occurrence[];
+  deletingTaskId: TaskId | null;
+  onToggleTask: (occurrence: Occurrence) => void;
+  onDeleteTask: (id: TaskId) => void;
+  onRepeatChange: (id: TaskId, recurrence: Recurrence | null) => void;
+  onDueTimeChange: (id: TaskId, time: string | null) => void;
+  onLongPressStart: (id: TaskId) => void;
   onLongPressEnd: () => void;
   onHoverDeleteArea: (isHovering: boolean) => void;
 }
 
 export function TaskList({
-  tasks,
+  occurrences,
   deletingTaskId,
   onToggleTask,
   onDeleteTask,
+  onRepeatChange,
+  onDueTimeChange,
   onLongPressStart,
   onLongPressEnd,
   onHoverDeleteArea
 }: TaskListProps) {
   return (
     <div className="space-y-2">
-      {tasks.map((task) => (
+      {occurrences.map((occurrence) => (
         <TaskItem
-          key={task.id}
-          task={task}
+          key={`${occurrence.task.id}:${occurrence.date}`}
+          occurrence={occurrence}
           deletingTaskId={deletingTaskId}
           onToggle={onToggleTask}
           onDelete={onDeleteTask}
+          onRepeatChange={onRepeatChange}
+          onDueTimeChange={onDueTimeChange}
           onLongPressStart={onLongPressStart}
           onLongPressEnd={onLongPressEnd}
           onHoverDeleteArea={onHoverDeleteArea}
@@ -0,0 +1,98 @@
+import dayjs from 'dayjs';
+import { cn } from '@/lib/utils';
+import { Button } from '@/components/ui/button';
+import {
+  Dialog,
+  DialogContent,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle
+} from '@/components/ui/dialog';
+import type { TaskDraft, TaskPreview } from '@/types/commands';
+
+interface TaskPreviewDialogProps {
+  preview: TaskPreview | null;
+  isParsing: boolean;
+  onRemoveDraft: (index: number) => void;
+  onUseLlm: () => void;
+  onConfirm: (drafts: TaskDraft[]) => void;
+  onCancel: () => void;
+}
+
+const priorityStyles = {
+  high: 'text-red-600 dark:text-red-400',
+  medium: 'text-amber-600 dark:text-amber-400',
+  low: 'text-gray-500 dark:text-gray-400'
+};
+
+export function TaskPreviewDialog({
+  preview,
+  isParsing,
+  onRemoveDraft,
+  onUseLlm,
+  onConfirm,
+  onCancel
+}: TaskPreviewDialogProps) {
+  const drafts = preview?.drafts ?? [];
+
+  return (
+    <Dialog open={preview !== null} onOpenChange={(open) => !open && onCancel()}>
+      <DialogContent className="sm:max-w-[600px] max-h-[85vh] flex flex-col">
+        <DialogHeader className="border-b pb-4">
+          <DialogTitle className="text-md font-normal leading-normal tracking-wide">
+            {drafts.length === 1
+              ? 'Add this task?'
+              : `Add these ${drafts.length} tasks?`}
+          </DialogTitle>
+        </DialogHeader>
+        <ul className="px-1 py-2 overflow-y-auto space-y-3">
+          {drafts.map((draft, index) => (
+            <li key={index} className="flex items-start justify-between gap-2">
+              <div>
+                <div className="text-gray-900 dark:text-gray-100">
+                  {draft.content}
+                </div>
+                <div className="flex flex-wrap gap-2 text-xs text-gray-500 dark:text-gray-400">
+                  {draft.assignedDate && (
+                    <span>{dayjs(draft.assignedDate).format('ddd, MMM D')}</span>
+                  )}
+                  {draft.priority && (
+                    <span className={cn(priorityStyles[draft.priority])}>
+                      {draft.priority} priority
+                    </span>
+                  )}
+                  {draft.tags.map((tag) => (
+                    <span key={tag}>#{tag}</span>
+                  ))}
+                </div>
+              </div>
+              <button
+                className="text-gray-400 hover:text-red-500 text-sm"
+                onClick={() => onRemoveDraft(index)}
+                aria-label="Remove task"
+              >
+                ✕
+              </button>
+            </li>
+          ))}
+        </ul>
+        <DialogFooter className="border-t pt-4 gap-2">
+          {preview?.parser === 'local' && (
+            <Button variant="ghost" onClick={onUseLlm} disabled={isParsing}>
+              {isParsing ? 'Asking the assistant…' : 'Split with AI'}
+            </Button>
+          )}
+          <Button variant="outline" onClick={onCancel}>
+            Cancel
+          </Button>
+          <Button
+            onClick={() => onConfirm(drafts)}
+            disabled={drafts.length === 0 || isParsing}
+          >
+            Add {drafts.length === 1 ? 'task' : 'tasks'}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
@@ -1,36 +1,19 @@
-export interface Task {
-  id: number;
-  content: string;
-  completed: boolean;
-  assignedDate: string;
-  __tempId?: number;
+import type { Task as TaskModel, TaskOccurrence } from '@/types/commands';
+
+// A task in the list. Ones added optimistically keep a temporary ID until
+// create_task answers with the real task.
+export interface Task extends TaskModel {
+  __tempId?: string;
   isNewTask?: boolean;
 }
 
+// One day's entry in the week view, see list_task_occurrences
+export interface Occurrence extends TaskOccurrence {
+  task: Task;
+}
+
 export interface DaySection {
   day: string;
   date?: string;
   temperature?: string;
 }
-
-export interface TranscriptionEvent {
-  payload: string;
-}
-
-export interface ApiTask {
-  id: string;
-  task: string;
-  done: boolean;
-  assignedDate: string;
-  createdAt?: string;
-  updatedAt?: string;
-}
-
-export function transformApiTask(apiTask: ApiTask): Task {
-  return {
-    id: Number(apiTask.id),
-    content: apiTask.task,
-    completed: apiTask.done,
-    assignedDate: apiTask.assignedDate || new Date().toISOString()
-  };
-}
@@ -0,0 +1,76 @@
+import { useCallback, useEffect, useState } from 'react';
+import * as KindeAuth from '@kinde-oss/kinde-auth-react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { ProfileChanged } from '@/types/commands';
+
+// Where the Kinde SDK keeps its refresh token with
+// `isDangerouslyUseLocalStorage` on, see main.tsx. Rust refreshes the session
+// from its own copy and sends back the rotated token, so the SDK restores the
+// next launch from one that's still valid.
+const REFRESH_TOKEN_KEY = 'kinde_refresh_token';
+
+/**
+ * Hands the Kinde tokens to the Rust side, which makes every backend call.
+ * Returns true once the session has been handed over.
+ */
+export function useSessionSync() {
+  const { isAuthenticated, getToken, logout } = KindeAuth.useKindeAuth();
+  const [ready, setReady] = useState(false);
+
+  const syncSession = useCallback(async () => {
+    if (!isAuthenticated || !getToken) {
+      setReady(false);
+      return;
+    }
+
+    try {
+      const accessToken = await getToken();
+      if (!accessToken) return;
+
+      await invoke('set_session', {
+        accessToken,
+        refreshToken: localStorage.getItem(REFRESH_TOKEN_KEY) ?? undefined
+      });
+      setReady(true);
+    } catch (error) {
+      console.error('Error syncing session:', error);
+    }
+  }, [isAuthenticated, getToken]);
+
+  useEffect(() => {
+    syncSession();
+  }, [syncSession]);
+
+  useEffect(() => {
+    const unlistenRefresh = listen('session-refresh-needed', () => {
+      syncSession();
+    });
+    const unlistenExpired = listen('session-expired', () => {
+      syncSession();
+    });
+    const unlistenRefreshed = listen<string>('session-refreshed', (event) => {
+      localStorage.setItem(REFRESH_TOKEN_KEY, event.payload);
+    });
+
+    // The new profile brings its own session. Without one, sign out of
+    // Kinde too so the next login belongs to that profile.
+    const unlistenProfile = listen<ProfileChanged>(
+      'profile-changed',
+      (event) => {
+        if (!event.payload.signedIn) {
+          logout();
+        }
+      }
+    );
+
+    return () => {
+      unlistenRefresh.then((unlistenFn) => unlistenFn());
+      unlistenExpired.then((unlistenFn) => unlistenFn());
+      unlistenRefreshed.then((unlistenFn) => unlistenFn());
+      unlistenProfile.then((unlistenFn) => unlistenFn());
+    };
+  }, [syncSession, logout]);
+
+  return ready;
+}
@@ -0,0 +1,56 @@
+import { useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { emit, listen } from '@tauri-apps/api/event';
+import { ToastAction } from '@/components/ui/toast';
+import { toast } from '@/hooks/use-toast';
+import type { TaskReminder } from '@/types/commands';
+
+const SNOOZE_MINUTES = 10;
+
+/**
+ * Shows the reminders Rust sends as `task-reminder` with buttons to snooze or
+ * complete the task, which desktop notifications can't offer. Completing one
+ * sends `reminder-completed` so the tasks page can catch up.
+ */
+export function useTaskReminders() {
+  useEffect(() => {
+    const unlisten = listen<TaskReminder>('task-reminder', (event) => {
+      const { task, date, overdue } = event.payload;
+
+      const snooze = () =>
+        invoke('snooze_task_reminder', {
+          taskId: task.id,
+          date,
+          minutes: SNOOZE_MINUTES
+        }).catch((error) => console.error('Error snoozing reminder:', error));
+
+      const complete = () =>
+        invoke('set_occurrence_completed', {
+          taskId: task.id,
+          date,
+          completed: true
+        })
+          .then(() => emit('reminder-completed'))
+          .catch((error) => console.error('Error completing task:', error));
+
+      toast({
+        title: overdue ? 'Overdue task' : 'Task due',
+        description: task.content,
+        action: (
+          <div className="flex gap-2">
+            <ToastAction altText="Snooze" onClick={snooze}>
+              Snooze {SNOOZE_MINUTES} min
+            </ToastAction>
+            <ToastAction altText="Mark as done" onClick={complete}>
+              Done
+            </ToastAction>
+          </div>
+        )
+      });
+    });
+
+    return () => {
+      unlisten.then((unlistenFn) => unlistenFn());
+    };
+  }, []);
+}
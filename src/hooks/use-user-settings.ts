@@ -1,22 +1,11 @@
 import { useState, useEffect } from 'react';
 import * as KindeAuth from '@kinde-oss/kinde-auth-react';
 import { invoke } from '@tauri-apps/api/core';
-
-export type UserSettingsResponse = {
-  id: string;
-  userId: string;
-  lat: number | null;
-  lon: number | null;
-  city: string | null;
-  state: string | null;
-  country: string | null;
-  units: 'imperial' | 'metric';
-  language: string;
-};
+import { UserSettings } from '@/types/commands';
 
 export function useUserSettings() {
   const { logout, login, isAuthenticated, getToken } = KindeAuth.useKindeAuth();
-  const [settings, setSettings] = useState<UserSettingsResponse | null>(null);
+  const [settings, setSettings] = useState<UserSettings | null>(null);
   const [isLoading, setIsLoading] = useState(true);
   const [error, setError] = useState<Error | null>(null);
 
@@ -30,11 +19,8 @@ export function useUserSettings() {
       try {
         setIsLoading(true);
         setError(null);
-        const token = await getToken();
-        const userSettings = await invoke<UserSettingsResponse>(
-          'get_user_settings',
-          { token }
-        );
+        const userSettings =
+          await invoke<UserSettings>('get_user_settings');
 
         setSettings(userSettings);
       } catch (err) {
@@ -59,11 +45,8 @@ export function useUserSettings() {
     error,
     refetch: async () => {
       if (!isAuthenticated || !getToken) return;
-      const token = await getToken();
-      const userSettings = await invoke<UserSettingsResponse>(
-        'get_user_settings',
-        { token }
-      );
+      const userSettings =
+        await invoke<UserSettings>('get_user_settings');
       setSettings(userSettings);
     }
   };
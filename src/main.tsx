@@ -11,7 +11,9 @@ ReactDOM.createRoot(document.getElementById('root') as HTMLElement).render(
       domain="https://jeffai.kinde.com"
       logoutUri={window.location.origin}
       redirectUri={window.location.origin}
-      isDangerouslyUseLocalStorage={process.env.NODE_ENV === 'development'}
+      // The webview's storage is private to the app. Keeping the refresh
+      // token there lets the Rust side read it and renew the session itself
+      isDangerouslyUseLocalStorage
       onRedirectCallback={async (userData) => {
         await invoke('set_user', { userData });
       }}
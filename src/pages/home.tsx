@@ -38,7 +38,6 @@ export default function Home() {
     }
 
     try {
-      const token = await getToken();
       await register('CommandOrControl+Shift+J', (event) => {
         if (event.state === 'Pressed') {
           play();
@@ -47,7 +46,7 @@ export default function Home() {
 
         if (event.state === 'Released') {
           play();
-          invoke('stop_recording', { token, refine: true });
+          invoke('stop_recording', { refine: true });
         }
       });
     } catch (error) {
@@ -59,15 +58,28 @@ export default function Home() {
     handleShortcut();
   }, [handleShortcut]);
 
+  // Push-to-talk is driven entirely from Rust, we only play feedback
+  useEffect(() => {
+    const unlistenStarted = listen('push-to-talk-started', () => {
+      play();
+    });
+    const unlistenStopped = listen('push-to-talk-stopped', () => {
+      play();
+    });
+
+    return () => {
+      unlistenStarted.then((unlistenFn) => unlistenFn());
+      unlistenStopped.then((unlistenFn) => unlistenFn());
+    };
+  }, [play]);
+
   const captureUser = useCallback(async () => {
     if (!isAuthenticated || !getToken || !getUser) {
       return;
     }
     const authUser = getUser();
-    const token = await getToken();
 
     await invoke('capture_user', {
-      token,
       authUser
     });
   }, [getToken, getUser, isAuthenticated]);
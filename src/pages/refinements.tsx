@@ -22,13 +22,8 @@ export default function Refinements() {
     }
 
     try {
-      const token = await getToken();
-      const fetchedRefinements = await invoke<Array<Refinement>>(
-        'get_all_refinements',
-        {
-          token
-        }
-      );
+      const fetchedRefinements =
+        await invoke<Array<Refinement>>('get_all_refinements');
       setRefinements(fetchedRefinements);
     } finally {
       setIsLoading(false);
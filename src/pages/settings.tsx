@@ -40,13 +40,11 @@ import {
 import { cn } from '@/lib/utils';
 import { Skeleton } from '@/components/ui/skeleton';
 import { invoke } from '@tauri-apps/api/core';
-import { WeatherLocationResult } from '@/types/commands';
+import { GeocodeResult, UserSettings } from '@/types/commands';
 import { useMediaQuery } from '@/hooks/use-media-query';
 import { useToast } from '@/hooks/use-toast';
-import {
-  UserSettingsResponse,
-  useUserSettings
-} from '@/hooks/use-user-settings';
+import { ProfileSwitcher } from '@/components/profile-switcher';
+import { useUserSettings } from '@/hooks/use-user-settings';
 
 // Add skeleton components at the top of the file after imports
 const AccountSkeleton = () => (
@@ -131,8 +129,8 @@ type PreferencesFormValues = {
 interface LocationSelectionProps {
   open: boolean;
   onOpenChange: (open: boolean) => void;
-  locations: WeatherLocationResult[];
-  onLocationSelect: (location: WeatherLocationResult) => void;
+  locations: GeocodeResult[];
+  onLocationSelect: (location: GeocodeResult) => void;
 }
 
 function LocationSelection({
@@ -243,10 +241,10 @@ export default function Settings() {
   });
 
   // Add these new state variables
-  const [locations, setLocations] = useState<WeatherLocationResult[]>([]);
+  const [locations, setLocations] = useState<GeocodeResult[]>([]);
   const [isLocationSelectOpen, setIsLocationSelectOpen] = useState(false);
   const [selectedLocation, setSelectedLocation] =
-    useState<WeatherLocationResult | null>(null);
+    useState<GeocodeResult | null>(null);
 
   // Fetch existing settings on component mount
   useEffect(() => {
@@ -254,11 +252,8 @@ export default function Settings() {
       if (!isAuthenticated || !getToken) return;
 
       try {
-        const token = await getToken();
-        const settings = await invoke<UserSettingsResponse>(
-          'get_user_settings',
-          { token }
-        );
+        const settings =
+          await invoke<UserSettings>('get_user_settings');
 
         console.log('Fetched settings:', settings);
 
@@ -307,11 +302,9 @@ export default function Settings() {
     if (!isAuthenticated || !getToken) return;
 
     try {
-      const token = await getToken();
-      const response = await invoke<Array<WeatherLocationResult>>(
+      const response = await invoke<Array<GeocodeResult>>(
         'get_weather_location',
         {
-          token,
           location: {
             city: data.city,
             state: data.state,
@@ -340,7 +333,6 @@ export default function Settings() {
           : 'create_user_settings';
 
         await invoke(method, {
-          token,
           settings: settingsData
         });
 
@@ -372,7 +364,6 @@ export default function Settings() {
     if (!isAuthenticated || !getToken) return;
 
     try {
-      const token = await getToken();
       const settingsData = {
         lat: selectedLocation?.lat || null,
         lon: selectedLocation?.lon || null,
@@ -388,7 +379,6 @@ export default function Settings() {
         : 'create_user_settings';
 
       await invoke(method, {
-        token,
         settings: settingsData
       });
 
@@ -407,14 +397,13 @@ export default function Settings() {
   };
 
   // Update the location selection handler
-  const handleLocationSelect = async (location: WeatherLocationResult) => {
+  const handleLocationSelect = async (location: GeocodeResult) => {
     if (!isAuthenticated || !getToken) return;
 
     try {
       setSelectedLocation(location);
       setIsLocationSelectOpen(false);
 
-      const token = await getToken();
       const formValues = preferencesForm.getValues(); // Get all form values at once
 
       const settingsData = {
@@ -432,7 +421,6 @@ export default function Settings() {
         : 'create_user_settings';
 
       await invoke(method, {
-        token,
         settings: settingsData
       });
 
@@ -469,10 +457,14 @@ export default function Settings() {
           <p className="text-gray-500 dark:text-gray-400 mb-4">
             Manage your account settings
           </p>
+          <ProfileSwitcher />
           <Button
             variant="outline"
             className="w-full hover:border-red-500"
-            onClick={() => logout()}
+            onClick={async () => {
+              await invoke('logout');
+              logout();
+            }}
           >
             Logout
           </Button>
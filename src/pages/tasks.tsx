@@ -11,17 +11,25 @@ import { MicrophoneToggle } from '@/components/microphone-toggle';
 import { Button } from '@/components/ui/button';
 import * as motion from 'motion/react-client';
 import { useUserSettings } from '@/hooks/use-user-settings';
-import { WeatherResponse } from '@/types/commands';
-import {
-  Task,
-  ApiTask,
-  DaySection,
-  TranscriptionEvent,
-  transformApiTask
-} from '@/components/tasks/types';
+import type {
+  NewTask,
+  Recurrence,
+  Task as TaskModel,
+  TaskChanges,
+  TaskDraft,
+  TaskId,
+  TaskOccurrence,
+  TaskPreview,
+  WeatherResponse
+} from '@/types/commands';
+import { Occurrence, DaySection } from '@/components/tasks/types';
 import { WeekSection } from '@/components/tasks/WeekSection';
+import { TaskPreviewDialog } from '@/components/tasks/TaskPreviewDialog';
 import { TaskList } from '@/components/tasks/TaskList';
 
+// The week view always shows Monday to Friday of the current week
+const currentMonday = () => dayjs().startOf('week').add(1, 'day');
+
 export default function Tasks() {
   const {
     isAuthenticated,
@@ -30,15 +38,18 @@ export default function Tasks() {
   } = useUserSettings();
 
   const [selectedDay, setSelectedDay] = useState<string>('MONDAY');
-  const [tasks, setTasks] = useState<Task[]>([]);
+  const [occurrences, setOccurrences] = useState<Occurrence[]>([]);
   const [play] = useSound(recordSfx);
   const [isRecording, setIsRecording] = useState(false);
   const [isProcessing, setIsProcessing] = useState(false);
-  const [deletingTaskId, setDeletingTaskId] = useState<number | null>(null);
+  const [deletingTaskId, setDeletingTaskId] = useState<TaskId | null>(null);
   const deleteTimeoutRef = useRef<NodeJS.Timeout | null>(null);
   const longPressTimeout = useRef<NodeJS.Timeout | null>(null);
   const [isHoveringDeleteArea, setIsHoveringDeleteArea] = useState(false);
   const [weekSections, setWeekSections] = useState<DaySection[]>([]);
+  const [transcript, setTranscript] = useState('');
+  const [preview, setPreview] = useState<TaskPreview | null>(null);
+  const [isParsing, setIsParsing] = useState(false);
 
   const fetchWeatherData = useCallback(
     async (days: DaySection[]) => {
@@ -52,11 +63,9 @@ export default function Tasks() {
       }
 
       try {
-        const token = await getToken();
         const weatherData = await invoke<WeatherResponse>(
           'get_weather_forecast',
           {
-            token,
             lat: existingSettings.lat,
             lon: existingSettings.lon
           }
@@ -91,8 +100,7 @@ export default function Tasks() {
 
   useEffect(() => {
     const generateWeekSections = async () => {
-      const today = dayjs();
-      const monday = today.startOf('week').add(1, 'day');
+      const monday = currentMonday();
 
       const days = ['MONDAY', 'TUESDAY', 'WEDNESDAY', 'THURSDAY', 'FRIDAY'].map(
         (day, index) => ({
@@ -109,50 +117,81 @@ export default function Tasks() {
     generateWeekSections();
   }, [fetchWeatherData]);
 
-  const fetchTasks = useCallback(async () => {
+  // Repeating tasks come back once for every day they fall on this week
+  const fetchOccurrences = useCallback(async () => {
     if (!isAuthenticated || !getToken) return;
 
+    const monday = currentMonday();
     try {
-      const token = await getToken();
-      const response = await invoke<ApiTask[]>('fetch_tasks', { token });
-      if (Array.isArray(response)) {
-        const transformedTasks = response.map(transformApiTask);
-        setTasks(transformedTasks);
-      }
+      const response = await invoke<TaskOccurrence[]>(
+        'list_task_occurrences',
+        {
+          from: monday.format('YYYY-MM-DD'),
+          to: monday.add(4, 'day').format('YYYY-MM-DD')
+        }
+      );
+      setOccurrences(response);
     } catch (error) {
       console.error('Error fetching tasks:', error);
     }
   }, [getToken, isAuthenticated]);
 
   useEffect(() => {
-    fetchTasks();
-  }, [fetchTasks]);
+    fetchOccurrences();
+  }, [fetchOccurrences]);
+
+  // Sent when a sync pulls in changes made elsewhere, or a task is completed
+  // from its reminder
+  useEffect(() => {
+    const unlistenTasks = listen<TaskModel[]>('tasks-changed', () => {
+      fetchOccurrences();
+    });
+    const unlistenReminder = listen('reminder-completed', () => {
+      fetchOccurrences();
+    });
+
+    return () => {
+      unlistenTasks.then((unlistenFn) => unlistenFn());
+      unlistenReminder.then((unlistenFn) => unlistenFn());
+    };
+  }, [fetchOccurrences]);
+
+  // Dictation on this page is split into tasks and previewed before any of
+  // them are created
+  const parseTranscript = useCallback(
+    async (text: string, useLlm: boolean) => {
+      setIsParsing(true);
+      try {
+        const result = await invoke<TaskPreview>('parse_tasks', {
+          transcript: text,
+          useLlm
+        });
+        setTranscript(text);
+        setPreview(result);
+      } catch (error) {
+        console.error('Error parsing tasks:', error);
+      } finally {
+        setIsParsing(false);
+      }
+    },
+    []
+  );
 
   useEffect(() => {
-    const unlisten = listen(
-      'transcription-complete',
-      (event: TranscriptionEvent) => {
-        const selectedSection = weekSections.find((s) => s.day === selectedDay);
-        if (!selectedSection || !selectedSection.date) return;
-
-        const newTask: Task = {
-          id: Date.now(),
-          content: event.payload,
-          completed: false,
-          assignedDate: dayjs(selectedSection.date).toISOString()
-        };
-        setTasks((prev) => [...prev, newTask]);
+    const unlisten = listen<string>(
+      'refined-transcription-complete',
+      (event) => {
+        if (event.payload) parseTranscript(event.payload, false);
       }
     );
 
     return () => {
       unlisten.then((unlistenFn) => unlistenFn());
     };
-  }, [selectedDay, weekSections]);
+  }, [parseTranscript]);
 
   const handleRecording = async () => {
     if (!isAuthenticated || !getToken) return;
-    const token = await getToken();
 
     if (!isRecording) {
       play();
@@ -163,93 +202,152 @@ export default function Tasks() {
       setIsRecording(false);
       setIsProcessing(true);
       try {
-        await invoke('stop_recording', { token, refine: true });
+        await invoke('stop_recording', { refine: true });
       } finally {
         setIsProcessing(false);
       }
     }
   };
 
-  const handleAddTask = async (content: string, isNewTask?: boolean) => {
-    if (!content.trim() || !isAuthenticated || !getToken) return;
-
-    const tempId = Date.now();
-    const optimisticTask: Task = {
-      id: tempId,
-      content,
-      completed: false,
-      assignedDate: dayjs(
-        weekSections.find((s) => s.day === selectedDay)?.date
-      ).toISOString(),
-      __tempId: tempId,
-      isNewTask
+  const selectedDate = () =>
+    dayjs(weekSections.find((s) => s.day === selectedDay)?.date).toISOString();
+
+  const addTask = async (draft: NewTask, isNewTask?: boolean) => {
+    const tempId = `temp-${Date.now()}-${Math.random()}`;
+    const optimistic: Occurrence = {
+      task: {
+        ...draft,
+        id: tempId,
+        completed: false,
+        priority: draft.priority ?? null,
+        recurrence: draft.recurrence ?? null,
+        dueTime: draft.dueTime ?? null,
+        createdAt: null,
+        updatedAt: null,
+        __tempId: tempId,
+        isNewTask
+      },
+      date: dayjs(draft.assignedDate).format('YYYY-MM-DD'),
+      completed: false
     };
 
-    setTasks((prev) => [...prev, optimisticTask]);
+    setOccurrences((prev) => [...prev, optimistic]);
 
     try {
-      const token = await getToken();
-      const response = await invoke<ApiTask>('create_task', {
-        token,
-        content,
-        assignedDate: optimisticTask.assignedDate
-      });
+      const created = await invoke<TaskModel>('create_task', { task: draft });
 
-      setTasks((prev) =>
-        prev.map((task) =>
-          task.__tempId === tempId ? transformApiTask(response) : task
+      setOccurrences((prev) =>
+        prev.map((occurrence) =>
+          occurrence.task.__tempId === tempId
+            ? { ...occurrence, task: created }
+            : occurrence
         )
       );
     } catch (error) {
       console.error('Error creating task:', error);
-      setTasks((prev) => prev.filter((task) => task.__tempId !== tempId));
+      setOccurrences((prev) =>
+        prev.filter((occurrence) => occurrence.task.__tempId !== tempId)
+      );
     }
   };
 
-  const handleToggleTask = async (id: number) => {
-    const task = tasks.find((t) => t.id === id);
-    if (!task || !isAuthenticated || !getToken) return;
+  const handleAddTask = async (content: string, isNewTask?: boolean) => {
+    if (!content.trim() || !isAuthenticated || !getToken) return;
+
+    await addTask({ content, assignedDate: selectedDate(), tags: [] }, isNewTask);
+  };
 
-    setTasks((prev) =>
-      prev.map((task) =>
-        task.id === id ? { ...task, completed: !task.completed } : task
+  // Drafts without a day of their own go to the selected one
+  const handleConfirmDrafts = async (drafts: TaskDraft[]) => {
+    setPreview(null);
+    for (const draft of drafts) {
+      await addTask({
+        content: draft.content,
+        assignedDate: draft.assignedDate ?? selectedDate(),
+        priority: draft.priority ?? undefined,
+        tags: draft.tags
+      });
+    }
+  };
+
+  const handleRemoveDraft = (index: number) => {
+    setPreview((prev) =>
+      prev && { ...prev, drafts: prev.drafts.filter((_, i) => i !== index) }
+    );
+  };
+
+  // Only this day's occurrence of a repeating task is toggled
+  const handleToggleTask = async ({ task, date, completed }: Occurrence) => {
+    if (!isAuthenticated || !getToken) return;
+
+    setOccurrences((prev) =>
+      prev.map((occurrence) =>
+        occurrence.task.id === task.id && occurrence.date === date
+          ? { ...occurrence, completed: !completed }
+          : occurrence
       )
     );
 
     try {
-      const token = await getToken();
-      await invoke('update_task', {
-        token,
-        taskId: id,
-        completed: !task.completed
+      await invoke('set_occurrence_completed', {
+        taskId: task.id,
+        date,
+        completed: !completed
       });
     } catch (error) {
       console.error('Error updating task:', error);
-      setTasks((prev) =>
-        prev.map((task) =>
-          task.id === id ? { ...task, completed: task.completed } : task
-        )
-      );
+      fetchOccurrences();
     }
   };
 
-  const handleDeleteTask = async (taskId: number) => {
+  // The time input gives HH:MM, chrono wants the seconds too
+  const handleDueTimeChange = async (taskId: TaskId, time: string | null) => {
     if (!isAuthenticated || !getToken) return;
 
-    setTasks((prev) => prev.filter((task) => task.id !== taskId));
+    try {
+      const changes: TaskChanges = { dueTime: time && `${time}:00` };
+      await invoke('update_task', { taskId, changes });
+    } catch (error) {
+      console.error('Error updating task:', error);
+    } finally {
+      fetchOccurrences();
+    }
+  };
+
+  const handleRepeatChange = async (
+    taskId: TaskId,
+    recurrence: Recurrence | null
+  ) => {
+    if (!isAuthenticated || !getToken) return;
+
+    try {
+      const changes: TaskChanges = { recurrence };
+      await invoke('update_task', { taskId, changes });
+    } catch (error) {
+      console.error('Error updating task:', error);
+    } finally {
+      fetchOccurrences();
+    }
+  };
+
+  const handleDeleteTask = async (taskId: TaskId) => {
+    if (!isAuthenticated || !getToken) return;
+
+    setOccurrences((prev) =>
+      prev.filter((occurrence) => occurrence.task.id !== taskId)
+    );
 
     try {
-      const token = await getToken();
-      await invoke('delete_task', { token, taskId });
+      await invoke('delete_task', { taskId });
     } catch (error) {
       console.error('Error deleting task:', error);
-      fetchTasks();
+      fetchOccurrences();
     } finally {
       setDeletingTaskId(null);
     }
   };
 
-  const handleLongPressStart = (taskId: number) => {
+  const handleLongPressStart = (taskId: TaskId) => {
     longPressTimeout.current = setTimeout(() => {
       setDeletingTaskId(taskId);
     }, 500);
@@ -277,8 +375,8 @@ export default function Tasks() {
     }
   }, [deletingTaskId, isHoveringDeleteArea]);
 
-  const tasksForSelectedDay = tasks.filter((t) =>
-    dayjs(t.assignedDate).isSame(
+  const occurrencesForSelectedDay = occurrences.filter((o) =>
+    dayjs(o.date).isSame(
       weekSections.find((s) => s.day === selectedDay)?.date,
       'day'
     )
@@ -300,10 +398,12 @@ export default function Tasks() {
             onAddTask={handleAddTask}
           >
             <TaskList
-              tasks={tasksForSelectedDay}
+              occurrences={occurrencesForSelectedDay}
               deletingTaskId={deletingTaskId}
               onToggleTask={handleToggleTask}
               onDeleteTask={handleDeleteTask}
+              onRepeatChange={handleRepeatChange}
+              onDueTimeChange={handleDueTimeChange}
               onLongPressStart={handleLongPressStart}
               onLongPressEnd={handleLongPressEnd}
               onHoverDeleteArea={setIsHoveringDeleteArea}
@@ -311,6 +411,14 @@ export default function Tasks() {
           </WeekSection>
         ))}
       </div>
+      <TaskPreviewDialog
+        preview={preview}
+        isParsing={isParsing}
+        onRemoveDraft={handleRemoveDraft}
+        onUseLlm={() => parseTranscript(transcript, true)}
+        onConfirm={handleConfirmDrafts}
+        onCancel={() => setPreview(null)}
+      />
     </div>
   );
 }
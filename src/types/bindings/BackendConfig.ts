@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BackendEnvironment } from "./BackendEnvironment";
+
+/**
+ * The backend in effect, as reported to the settings page.
+ */
+export type BackendConfig = { environment: BackendEnvironment, baseUrl: string, 
+/**
+ * Set when an environment variable overrides the saved preference, in
+ * which case changing it in settings has no effect until restart
+ */
+fromEnv: boolean, 
+/**
+ * Running against the in-process mock backend with a built-in user, the
+ * frontend skips the Kinde login
+ */
+mock: boolean, };
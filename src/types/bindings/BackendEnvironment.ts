@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which backend the app talks to. Stored in the preferences file and can be
+ * overridden per launch with `JEFF_AI_API_URL` or `JEFF_AI_ENV`.
+ */
+export type BackendEnvironment = { "environment": "production" } | { "environment": "staging" } | { "environment": "local" } | { "environment": "custom", "url": string };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WeatherCondition } from "./WeatherCondition";
+
+export type CurrentWeather = { dt: number, sunrise: number, sunset: number, temp: number, feels_like: number, pressure: number, humidity: number, dew_point: number, uvi: number, clouds: number, visibility: number, wind_speed: number, wind_deg: number, weather: Array<WeatherCondition>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DailyFeelsLike } from "./DailyFeelsLike";
+import type { DailyTemp } from "./DailyTemp";
+import type { WeatherCondition } from "./WeatherCondition";
+
+export type DailyWeather = { dt: number, sunrise: number, sunset: number, moonrise: number, moonset: number, moon_phase: number, summary: string, temp: DailyTemp, feels_like: DailyFeelsLike, pressure: number, humidity: number, dew_point: number, wind_speed: number, wind_deg: number, wind_gust: number, weather: Array<WeatherCondition>, clouds: number, pop: number, rain?: number, uvi: number, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type GeocodeResult = { name: string, lat: number, lon: number, country: string, state: string | null, local_names?: { [key in string]?: string }, };
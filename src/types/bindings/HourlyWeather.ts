@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HourlyRain } from "./HourlyRain";
+import type { WeatherCondition } from "./WeatherCondition";
+
+export type HourlyWeather = { dt: number, temp: number, feels_like: number, pressure: number, humidity: number, dew_point: number, uvi: number, clouds: number, visibility: number, wind_speed: number, wind_deg: number, wind_gust?: number, weather: Array<WeatherCondition>, pop: number, rain?: HourlyRain, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Priority } from "./Priority";
+import type { Recurrence } from "./Recurrence";
+
+/**
+ * What the frontend sends to create a task
+ */
+export type NewTask = { content: string, assignedDate: string, priority?: Priority, tags: Array<string>, recurrence?: Recurrence, dueTime?: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A separate account on the same machine, e.g. "Personal" and "Work".
+ */
+export type Profile = { id: string, name: string, createdAt: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Profile } from "./Profile";
+
+/**
+ * Payload of the `profile-changed` event.
+ */
+export type ProfileChanged = { profile: Profile, signedIn: boolean, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Profile } from "./Profile";
+
+/**
+ * All profiles and which one is active, kept in `profiles.json` in the app
+ * config dir.
+ */
+export type ProfileRegistry = { active: string, profiles: Array<Profile>, };
@@ -0,0 +1,31 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Day } from "./Day";
+import type { Frequency } from "./Frequency";
+
+/**
+ * How a task repeats. Covers the daily, weekly and monthly parts of
+ * iCalendar's RRULE, and is stored as one.
+ */
+export type Recurrence = { frequency: Frequency, 
+/**
+ * Every `interval` days, weeks or months
+ */
+interval: number, 
+/**
+ * Only on these days of the week. A weekly rule without any repeats on
+ * the day the task is assigned to.
+ */
+days: Array<Day>, 
+/**
+ * Monthly only: the day of the month, the task's own day when unset. In
+ * shorter months the last day is used instead.
+ */
+monthDay?: number, 
+/**
+ * No occurrences after this day
+ */
+until?: string, 
+/**
+ * No more than this many occurrences
+ */
+count?: number, };
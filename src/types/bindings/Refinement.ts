@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type Refinement = { id: string, userId: string, originalText: string, originalTextWordCount: number, refinedText: string, refinedTextWordCount: number, explanation: string | null, vector: Array<number>, createdAt: string, updatedAt: string, };
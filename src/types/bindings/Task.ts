@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Priority } from "./Priority";
+import type { Recurrence } from "./Recurrence";
+import type { TaskId } from "./TaskId";
+
+/**
+ * A task as the app and frontend see it. Every task command takes and
+ * returns this, `api::TaskRecord` is the worker's JSON for it.
+ */
+export type Task = { id: TaskId, content: string, completed: boolean, 
+/**
+ * ISO 8601 date or datetime of the day the task belongs to. Older tasks
+ * were created without one.
+ */
+assignedDate: string | null, 
+/**
+ * Priority and tags aren't stored by the worker yet, they only live in
+ * the local store
+ */
+priority: Priority | null, tags: Array<string>, 
+/**
+ * Repeats starting from `assigned_date`. Kept in the local store only,
+ * like priority and tags.
+ */
+recurrence: Recurrence | null, 
+/**
+ * Local time of day on `assigned_date` the task is due, a reminder is
+ * shown then. Local only too.
+ */
+dueTime: string | null, createdAt: string | null, updatedAt: string | null, };
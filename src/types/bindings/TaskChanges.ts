@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Priority } from "./Priority";
+import type { Recurrence } from "./Recurrence";
+
+/**
+ * A partial update, fields left out are kept as they are
+ */
+export type TaskChanges = { content?: string, completed?: boolean, assignedDate?: string, priority?: Priority, tags?: Array<string>, 
+/**
+ * `null` stops the task repeating
+ */
+recurrence?: Recurrence | null, 
+/**
+ * `null` removes the due time
+ */
+dueTime?: string | null, };
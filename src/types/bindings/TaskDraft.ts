@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Priority } from "./Priority";
+
+/**
+ * One task found in a transcript by `parse_tasks`, not saved until the user
+ * confirms it.
+ */
+export type TaskDraft = { content: string, 
+/**
+ * `YYYY-MM-DD`, when the transcript named a day
+ */
+assignedDate: string | null, priority: Priority | null, tags: Array<string>, };
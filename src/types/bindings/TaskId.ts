@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Identifies a task. The local store gives every task its own ID, which stays
+ * the same when a task created offline reaches the backend. The worker's IDs
+ * use this type too, they're only ever compared and put in URLs so they stay
+ * the strings it sends.
+ */
+export type TaskId = string;
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Task } from "./Task";
+
+/**
+ * One day's instance of a task in the week view. A task that doesn't repeat
+ * has a single occurrence on its assigned day.
+ */
+export type TaskOccurrence = { task: Task, date: string, 
+/**
+ * Completing one occurrence of a repeating task leaves the others as
+ * they are
+ */
+completed: boolean, };
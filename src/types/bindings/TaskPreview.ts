@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TaskDraft } from "./TaskDraft";
+import type { TaskParser } from "./TaskParser";
+
+export type TaskPreview = { drafts: Array<TaskDraft>, parser: TaskParser, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Task } from "./Task";
+
+/**
+ * Sent as `task-reminder` when an occurrence of a task comes due, so the
+ * window can offer to snooze or complete it.
+ */
+export type TaskReminder = { task: Task, date: string, 
+/**
+ * Due on an earlier day, typically missed while the app wasn't running
+ */
+overdue: boolean, };
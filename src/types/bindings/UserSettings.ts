@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Units } from "./Units";
+
+export type UserSettings = { id: string, userId: string, lat: number | null, lon: number | null, city: string | null, state: string | null, country: string | null, units: Units, language: string, };
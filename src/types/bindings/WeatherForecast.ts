@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CurrentWeather } from "./CurrentWeather";
+import type { DailyWeather } from "./DailyWeather";
+import type { HourlyWeather } from "./HourlyWeather";
+
+export type WeatherForecast = { lat: number, lon: number, timezone: string, timezone_offset: number, current: CurrentWeather, hourly: Array<HourlyWeather>, daily: Array<DailyWeather>, };
@@ -1,121 +1,59 @@
-export interface RefineTextResponse {
-  refinedText: string;
-  explanation?: string | undefined;
-}
-
-export interface MarkdownResponse {
-  markdown: string;
-}
-
-export interface Refinement {
-  id: string;
-  userId: string;
-  createdAt: string;
-  explanation?: string;
-  originalText: string;
-  originalTextWordCount: number;
-  refinedText: string;
-  refinedTextWordCount: number;
-  updatedAt: string;
-  vector: number[];
-}
-
-interface LocalName {
-  ar?: string; // Arabic
-  be?: string; // Belarusian
-  bg?: string; // Bulgarian
-  en: string; // English
-  [key: string]: string | undefined; // Allow for other language codes
-}
-
-export interface WeatherLocationResult {
-  name: string;
-  lat: number;
-  lon: number;
-  country: string;
-  state: string;
-  local_names?: LocalName;
-}
-
-interface Weather {
-  id: number;
-  main: string;
-  description: string;
-  icon: string;
-}
-
-interface CurrentWeather {
-  dt: number;
-  sunrise: number;
-  sunset: number;
-  temp: number;
-  feels_like: number;
-  pressure: number;
-  humidity: number;
-  dew_point: number;
-  uvi: number;
-  clouds: number;
-  visibility: number;
-  wind_speed: number;
-  wind_deg: number;
-  weather: Weather[];
-}
-
-interface HourlyWeather extends CurrentWeather {
-  wind_gust?: number;
-  pop: number;
-  rain?: {
-    '1h': number;
-  };
-}
-
-interface DailyTemp {
-  day: number;
-  min: number;
-  max: number;
-  night: number;
-  eve: number;
-  morn: number;
-}
-
-interface DailyFeelsLike {
-  day: number;
-  night: number;
-  eve: number;
-  morn: number;
-}
-
-interface DailyWeather {
-  dt: number;
-  sunrise: number;
-  sunset: number;
-  moonrise: number;
-  moonset: number;
-  moon_phase: number;
-  summary: string;
-  temp: DailyTemp;
-  feels_like: DailyFeelsLike;
-  pressure: number;
-  humidity: number;
-  dew_point: number;
-  wind_speed: number;
-  wind_deg: number;
-  wind_gust: number;
-  weather: Weather[];
-  clouds: number;
-  pop: number;
-  rain?: number;
-  uvi: number;
-}
-
-export interface WeatherResponse {
-  data: {
-    lat: number;
-    lon: number;
-    timezone: string;
-    timezone_offset: number;
-    current: CurrentWeather;
-    hourly: HourlyWeather[];
-    daily: DailyWeather[];
-  };
+// Types shared with the Rust side are generated from its structs into
+// ./bindings, run `cargo test` in src-tauri to regenerate them.
+export type { BackendConfig } from './bindings/BackendConfig';
+export type { BackendEnvironment } from './bindings/BackendEnvironment';
+export type { Day } from './bindings/Day';
+export type { Frequency } from './bindings/Frequency';
+export type { GeocodeResult } from './bindings/GeocodeResult';
+export type { MarkdownResponse } from './bindings/MarkdownResponse';
+export type { NewTask } from './bindings/NewTask';
+export type { Priority } from './bindings/Priority';
+export type { Profile } from './bindings/Profile';
+export type { ProfileChanged } from './bindings/ProfileChanged';
+export type { ProfileRegistry } from './bindings/ProfileRegistry';
+export type { Recurrence } from './bindings/Recurrence';
+export type { RefineTextResponse } from './bindings/RefineTextResponse';
+export type { Refinement } from './bindings/Refinement';
+export type { Task } from './bindings/Task';
+export type { TaskChanges } from './bindings/TaskChanges';
+export type { TaskDraft } from './bindings/TaskDraft';
+export type { TaskId } from './bindings/TaskId';
+export type { TaskOccurrence } from './bindings/TaskOccurrence';
+export type { TaskParser } from './bindings/TaskParser';
+export type { TaskPreview } from './bindings/TaskPreview';
+export type { TaskReminder } from './bindings/TaskReminder';
+export type { Units } from './bindings/Units';
+export type { UserSettings } from './bindings/UserSettings';
+export type { WeatherForecast } from './bindings/WeatherForecast';
+export type { WeatherResponse } from './bindings/WeatherResponse';
+
+export type AppErrorKind =
+  | 'unauthenticated'
+  | 'network'
+  | 'timeout'
+  | 'server'
+  | 'invalidResponse'
+  | 'validation'
+  | 'notFound'
+  | 'audio'
+  | 'io'
+  | 'cancelled'
+  | 'internal';
+
+// Shape of every error rejected by a Tauri command
+export interface AppError {
+  kind: AppErrorKind;
+  message: string;
+  retryable: boolean;
+  status?: number;
+  body?: string;
+}
+
+export function isAppError(error: unknown): error is AppError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    'kind' in error &&
+    'message' in error
+  );
 }